@@ -0,0 +1,244 @@
+//! Concentrated-liquidity math. Prices are `sqrt(y / x)` in Q64.64 fixed
+//! point and tick `i` sits at a price of `1.0001^i`. Token amounts are
+//! rounded so that the pool never pays out more than it takes in.
+
+use ethnum::U256;
+
+use crate::{math, QuoteError, Result, MAX_BPS};
+
+pub const MIN_TICK: i32 = -443_636;
+pub const MAX_TICK: i32 = 443_636;
+
+/// `sqrt_price_at_tick(MIN_TICK)` and `sqrt_price_at_tick(MAX_TICK)`.
+pub const MIN_SQRT_PRICE: u128 = 4_295_048_016;
+pub const MAX_SQRT_PRICE: u128 = 79_226_673_521_066_979_257_578_248_091;
+
+const Q64: u128 = 1 << 64;
+
+/// `2^64 / 1.0001^(2^i / 2)`: the factor contributed by bit `i` of `|tick|`.
+const RATIOS: [u128; 19] = [
+    0xfffcb933bd6fad37,
+    0xfff97272373d4132,
+    0xfff2e50f5f656932,
+    0xffe5caca7e10e4e6,
+    0xffcb9843d60f6159,
+    0xff973b41fa98c081,
+    0xff2ea16466c96a38,
+    0xfe5dee046a99a2a8,
+    0xfcbe86c7900a88ae,
+    0xf987a7253ac41317,
+    0xf3392b0822b70005,
+    0xe7159475a2c29b74,
+    0xd097f3bdfd2022b8,
+    0xa9f746462d870fdf,
+    0x70d869a156d2a1b8,
+    0x31be135f97d08fd9,
+    0x9aa508b5b7a84e1,
+    0x5d6af8dedb8119,
+    0x2216e584f5fa,
+];
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapStep {
+    pub sqrt_price_next: u128,
+    /// Amount taken from the input, fee excluded.
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+}
+
+/// Sqrt price at `tick`, as Q64.64.
+pub fn sqrt_price_at_tick(tick: i32) -> Result<u128> {
+    let abs = tick.unsigned_abs();
+    if abs > MAX_TICK as u32 {
+        return Err(QuoteError::InvalidTick);
+    }
+
+    // Price of `-|tick|`; each factor is below 2^64 so the product fits.
+    let mut ratio = Q64;
+    for (bit, factor) in RATIOS.iter().enumerate() {
+        if abs & (1 << bit) != 0 {
+            ratio = (ratio * factor) >> 64;
+        }
+    }
+
+    Ok(match tick > 0 {
+        true => u128::MAX / ratio,
+        false => ratio,
+    })
+}
+
+/// Greatest tick whose sqrt price is at most `sqrt_price`.
+pub fn tick_at_sqrt_price(sqrt_price: u128) -> Result<i32> {
+    if !(MIN_SQRT_PRICE..=MAX_SQRT_PRICE).contains(&sqrt_price) {
+        return Err(QuoteError::InvalidPrice);
+    }
+
+    let (mut low, mut high) = (MIN_TICK, MAX_TICK);
+    while low < high {
+        let mid = low + (high - low + 1) / 2;
+        match sqrt_price_at_tick(mid)? <= sqrt_price {
+            true => low = mid,
+            false => high = mid - 1,
+        }
+    }
+
+    Ok(low)
+}
+
+/// X covered by `liquidity` between two sqrt prices, `L * (b - a) / (a * b)`.
+pub fn amount_x_delta(sqrt_a: u128, sqrt_b: u128, liquidity: u128, round_up: bool) -> Result<u64> {
+    to_u64(x_delta(sqrt_a, sqrt_b, liquidity, round_up)?)
+}
+
+/// Y covered by `liquidity` between two sqrt prices, `L * (b - a)`.
+pub fn amount_y_delta(sqrt_a: u128, sqrt_b: u128, liquidity: u128, round_up: bool) -> Result<u64> {
+    to_u64(y_delta(sqrt_a, sqrt_b, liquidity, round_up))
+}
+
+/// Amounts backing `liquidity` over `[tick_lower, tick_upper)` with the pool
+/// at `tick` and `sqrt_price`.
+pub fn amounts_for_liquidity(
+    sqrt_price: u128,
+    tick: i32,
+    tick_lower: i32,
+    tick_upper: i32,
+    liquidity: u128,
+    round_up: bool,
+) -> Result<(u64, u64)> {
+    let sqrt_lower = sqrt_price_at_tick(tick_lower)?;
+    let sqrt_upper = sqrt_price_at_tick(tick_upper)?;
+
+    if tick < tick_lower {
+        Ok((amount_x_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?, 0))
+    } else if tick < tick_upper {
+        Ok((
+            amount_x_delta(sqrt_price, sqrt_upper, liquidity, round_up)?,
+            amount_y_delta(sqrt_lower, sqrt_price, liquidity, round_up)?,
+        ))
+    } else {
+        Ok((0, amount_y_delta(sqrt_lower, sqrt_upper, liquidity, round_up)?))
+    }
+}
+
+/// Sqrt price after adding `amount` of the input to a range with
+/// `liquidity`, rounded so the price moves no further than was paid for.
+pub fn next_sqrt_price_from_input(sqrt_price: u128, liquidity: u128, amount: u64, is_x: bool) -> Result<u128> {
+    if liquidity == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+
+    let (price, liquidity, amount) = (U256::from(sqrt_price), U256::from(liquidity), U256::from(amount));
+    let next = match is_x {
+        // L * p / (L + amount * p)
+        true => div(liquidity * price, liquidity + ((amount * price) >> 64), true),
+        // p + amount / L
+        false => price + (amount << 64) / liquidity,
+    };
+
+    u128::try_from(next).map_err(|_| QuoteError::Overflow)
+}
+
+/// Exact-in swap of up to `amount_remaining` within a single range, moving
+/// the price from `sqrt_price` towards `sqrt_target`. `fees` is taken from the
+/// input.
+pub fn compute_swap_step(
+    is_x: bool,
+    sqrt_price: u128,
+    sqrt_target: u128,
+    liquidity: u128,
+    amount_remaining: u64,
+    fees: u16,
+) -> Result<SwapStep> {
+    if fees >= MAX_BPS {
+        return Err(QuoteError::InvalidFee);
+    }
+
+    let amount_less_fee = math::mul_div(amount_remaining, (MAX_BPS - fees) as u64, MAX_BPS as u64)?;
+    let amount_to_target = match is_x {
+        true => x_delta(sqrt_target, sqrt_price, liquidity, true)?,
+        false => y_delta(sqrt_price, sqrt_target, liquidity, true),
+    };
+
+    let reached = U256::from(amount_less_fee) >= amount_to_target;
+    let sqrt_price_next = match reached {
+        true => sqrt_target,
+        false => next_sqrt_price_from_input(sqrt_price, liquidity, amount_less_fee, is_x)?,
+    };
+
+    let (amount_in, amount_out) = match is_x {
+        true => (
+            x_delta(sqrt_price_next, sqrt_price, liquidity, true)?,
+            y_delta(sqrt_price_next, sqrt_price, liquidity, false),
+        ),
+        false => (
+            y_delta(sqrt_price, sqrt_price_next, liquidity, true),
+            x_delta(sqrt_price, sqrt_price_next, liquidity, false)?,
+        ),
+    };
+    let (amount_in, amount_out) = (to_u64(amount_in)?, to_u64(amount_out)?);
+
+    // Short of the target the whole remainder is spent, so whatever the price
+    // move didn't use is fee.
+    let fee = match reached {
+        true => math::mul_div_ceil(amount_in, fees as u64, (MAX_BPS - fees) as u64)?,
+        false => amount_remaining.checked_sub(amount_in).ok_or(QuoteError::Underflow)?,
+    };
+
+    Ok(SwapStep {
+        sqrt_price_next,
+        amount_in,
+        amount_out,
+        fee,
+    })
+}
+
+/// Fee growth per unit of liquidity for `fee` earned by `liquidity`, Q64.64.
+pub fn fee_growth(fee: u64, liquidity: u128) -> u128 {
+    match liquidity {
+        0 => 0,
+        _ => ((fee as u128) << 64) / liquidity,
+    }
+}
+
+/// Fees earned by `liquidity` over a fee-growth increase of `growth`.
+pub fn fees_earned(growth: u128, liquidity: u128) -> Result<u64> {
+    to_u64((U256::from(growth) * U256::from(liquidity)) >> 64)
+}
+
+/// `liquidity + delta`.
+pub fn add_delta(liquidity: u128, delta: i128) -> Result<u128> {
+    match delta >= 0 {
+        true => liquidity.checked_add(delta as u128).ok_or(QuoteError::Overflow),
+        false => liquidity.checked_sub(delta.unsigned_abs()).ok_or(QuoteError::Underflow),
+    }
+}
+
+fn x_delta(sqrt_a: u128, sqrt_b: u128, liquidity: u128, round_up: bool) -> Result<U256> {
+    let (a, b) = (sqrt_a.min(sqrt_b), sqrt_a.max(sqrt_b));
+    if a == 0 {
+        return Err(QuoteError::InvalidPrice);
+    }
+
+    let scaled = div(U256::from(liquidity) * U256::from(b - a), U256::from(b), round_up);
+
+    Ok(div(scaled << 64, U256::from(a), round_up))
+}
+
+fn y_delta(sqrt_a: u128, sqrt_b: u128, liquidity: u128, round_up: bool) -> U256 {
+    let (a, b) = (sqrt_a.min(sqrt_b), sqrt_a.max(sqrt_b));
+
+    div(U256::from(liquidity) * U256::from(b - a), U256::from(Q64), round_up)
+}
+
+fn div(n: U256, d: U256, round_up: bool) -> U256 {
+    let q = n / d;
+    match round_up && q * d != n {
+        true => q + 1,
+        false => q,
+    }
+}
+
+fn to_u64(n: U256) -> Result<u64> {
+    u64::try_from(n).map_err(|_| QuoteError::Overflow)
+}
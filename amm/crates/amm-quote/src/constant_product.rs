@@ -0,0 +1,54 @@
+use crate::{math, QuoteError, Result, MAX_BPS};
+
+/// Exact-in constant-product swap. The fee is taken from the input and stays
+/// in the pool, so `k` never decreases. Returns `(withdraw, fee)`.
+pub fn swap(reserve_in: u64, reserve_out: u64, amount: u64, fees: u16) -> Result<(u64, u64)> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+    if fees > MAX_BPS {
+        return Err(QuoteError::InvalidFee);
+    }
+
+    let amount_in = math::mul_div(amount, (MAX_BPS - fees) as u64, MAX_BPS as u64)?;
+    let fee = amount - amount_in;
+
+    let denominator = reserve_in.checked_add(amount_in).ok_or(QuoteError::Overflow)?;
+    let withdraw = math::mul_div(reserve_out, amount_in, denominator)?;
+
+    Ok((withdraw, fee))
+}
+
+/// Portion of a single-sided deposit of `amount` that has to be swapped
+/// against `reserve` (same side) so that the remainder and the swap output
+/// are in the post-swap pool ratio. `fees` is in basis points.
+///
+/// Solves `s = (sqrt(((2 - f) R)^2 + 4 (1 - f) A R) - (2 - f) R) / (2 (1 - f))`.
+/// The result is homogeneous in `A` and `R`, so both are scaled down to keep
+/// the intermediate products within `u128`.
+pub fn zap_swap_amount(amount: u64, reserve: u64, fees: u16) -> Result<u64> {
+    const SCALE_BITS: u32 = 47;
+
+    if fees >= MAX_BPS {
+        return Err(QuoteError::InvalidFee);
+    }
+
+    let bits = 64 - amount.max(reserve).leading_zeros();
+    let shift = bits.saturating_sub(SCALE_BITS);
+
+    let a = (amount >> shift) as u128;
+    let r = (reserve >> shift) as u128;
+    let f = MAX_BPS as u128;
+    let fee = fees as u128;
+
+    let b = (2 * f - fee) * r;
+    let c = 4 * f * (f - fee) * a * r;
+    let root = math::sqrt(
+        b.checked_mul(b)
+            .and_then(|b2| b2.checked_add(c))
+            .ok_or(QuoteError::Overflow)?,
+    );
+    let swap = (root - b) / (2 * (f - fee));
+
+    Ok((swap << shift).min(amount as u128) as u64)
+}
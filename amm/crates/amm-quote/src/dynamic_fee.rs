@@ -0,0 +1,100 @@
+//! Volatility-scaled swap fees.
+//!
+//! Pools in dynamic-fee mode keep a volatility accumulator: the sum of the
+//! relative price moves of recent swaps, in basis points, decaying linearly
+//! to zero over `decay_period` seconds of inactivity. Each swap is charged a
+//! fee interpolated between `min_fees` and `max_fees` by how far the decayed
+//! accumulator is towards `max_volatility`, and then adds its own price move.
+//! A swap therefore never pays for its own impact, only for what came before.
+
+use ethnum::U256;
+
+use crate::{QuoteError, Result, MAX_BPS};
+
+/// Dynamic-fee parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DynamicFee {
+    pub min_fees: u16,
+    pub max_fees: u16,
+    /// Accumulated volatility, in basis points, at which `max_fees` applies.
+    pub max_volatility: u64,
+    /// Seconds for the accumulator to decay from any value back to zero.
+    pub decay_period: i64,
+}
+
+impl DynamicFee {
+    pub fn validate(&self) -> Result<()> {
+        if self.min_fees > self.max_fees || self.max_fees > MAX_BPS {
+            return Err(QuoteError::InvalidFee);
+        }
+        if self.max_volatility == 0 || self.decay_period <= 0 {
+            return Err(QuoteError::InvalidFee);
+        }
+
+        Ok(())
+    }
+
+    /// Accumulator left from `volatility` after `elapsed` seconds.
+    pub fn decay(&self, volatility: u64, elapsed: i64) -> u64 {
+        if elapsed <= 0 {
+            return volatility;
+        }
+        if elapsed >= self.decay_period {
+            return 0;
+        }
+
+        let remaining = (self.decay_period - elapsed) as u128;
+
+        (volatility as u128 * remaining / self.decay_period as u128) as u64
+    }
+
+    /// Fee in basis points for an accumulator at `volatility`, rounded up.
+    pub fn fee(&self, volatility: u64) -> u16 {
+        let spread = self.max_fees.saturating_sub(self.min_fees) as u128;
+        let volatility = volatility.min(self.max_volatility) as u128;
+        let max_volatility = self.max_volatility.max(1) as u128;
+
+        let extra = (spread * volatility).div_ceil(max_volatility);
+
+        self.min_fees + extra as u16
+    }
+}
+
+/// Relative move of the spot price `y / x` between two reserve states, in
+/// basis points, saturating at `u64::MAX`. Zero if either state is empty.
+pub fn price_change_bps(before_x: u64, before_y: u64, after_x: u64, after_y: u64) -> u64 {
+    if before_x == 0 || before_y == 0 || after_x == 0 || after_y == 0 {
+        return 0;
+    }
+
+    // after_y / after_x vs before_y / before_x, cross-multiplied.
+    let after = U256::from(after_y as u128 * before_x as u128);
+    let before = U256::from(before_y as u128 * after_x as u128);
+    let delta = match after >= before {
+        true => after - before,
+        false => before - after,
+    };
+
+    let bps = delta * U256::from(MAX_BPS as u128) / before;
+
+    match bps > U256::from(u64::MAX as u128) {
+        true => u64::MAX,
+        false => bps.as_u64(),
+    }
+}
+
+/// Relative move between two Q64.64 spot prices, in basis points, saturating
+/// at `u64::MAX`. Zero if `before` is. For curves whose price isn't the
+/// reserve ratio; see `price_change_bps` for constant product.
+pub fn spot_price_change_bps(before: u128, after: u128) -> u64 {
+    if before == 0 {
+        return 0;
+    }
+
+    let bps = U256::from(before.abs_diff(after)) * U256::from(MAX_BPS as u128) / U256::from(before);
+
+    match bps > U256::from(u64::MAX as u128) {
+        true => u64::MAX,
+        false => bps.as_u64(),
+    }
+}
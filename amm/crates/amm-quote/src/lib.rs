@@ -0,0 +1,196 @@
+//! Pool math shared by the `amm` program and off-chain clients.
+//!
+//! Every amount the program moves is computed here, so a quote built from a
+//! deserialized `Config` and the vault balances matches what the program
+//! would do with the same inputs.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod concentrated;
+pub mod constant_product;
+pub mod dynamic_fee;
+pub mod math;
+pub mod stable_swap;
+
+/// Fees and protocol shares are expressed in basis points.
+pub const MAX_BPS: u16 = 10_000;
+
+/// LP tokens locked forever by the first deposit.
+pub const MINIMUM_LIQUIDITY: u64 = 1_000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuoteError {
+    ZeroAmount,
+    NoLiquidity,
+    InsufficientBalance,
+    InsufficientInitialLiquidity,
+    InvalidFee,
+    Overflow,
+    Underflow,
+    NotConverged,
+    InvalidTick,
+    InvalidPrice,
+}
+
+pub type Result<T> = core::result::Result<T, QuoteError>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Curve {
+    ConstantProduct,
+    StableSwap { amp: u64 },
+}
+
+/// Everything needed to quote against a pool. Reserves exclude protocol fees
+/// that are owed to the treasury but still sit in the vaults.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pool {
+    pub reserve_x: u64,
+    pub reserve_y: u64,
+    pub lp_supply: u64,
+    pub fees: u16,
+    pub protocol_fee: u16,
+    pub curve: Curve,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SwapQuote {
+    /// Amount added to the input reserve, fee included.
+    pub deposit: u64,
+    /// Amount removed from the output reserve.
+    pub withdraw: u64,
+    /// Fee charged on the input, in the input token.
+    pub fee: u64,
+    /// Part of `fee` owed to the protocol.
+    pub protocol_fee: u64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LiquidityQuote {
+    pub x: u64,
+    pub y: u64,
+    pub lp: u64,
+}
+
+impl Pool {
+    /// Exact-in swap of `amount`, `is_x` selecting X as the input side.
+    pub fn swap(&self, is_x: bool, amount: u64) -> Result<SwapQuote> {
+        if amount == 0 {
+            return Err(QuoteError::ZeroAmount);
+        }
+
+        let (reserve_in, reserve_out) = match is_x {
+            true => (self.reserve_x, self.reserve_y),
+            false => (self.reserve_y, self.reserve_x),
+        };
+
+        let (withdraw, fee) = match self.curve {
+            Curve::ConstantProduct => constant_product::swap(reserve_in, reserve_out, amount, self.fees)?,
+            Curve::StableSwap { amp } => stable_swap::swap(amp, reserve_in, reserve_out, amount, self.fees)?,
+        };
+
+        if withdraw == 0 {
+            return Err(QuoteError::ZeroAmount);
+        }
+
+        Ok(SwapQuote {
+            deposit: amount,
+            withdraw,
+            fee,
+            protocol_fee: protocol_fee(fee, self.protocol_fee)?,
+        })
+    }
+
+    /// Amounts required to mint `lp` LP tokens, rounded up in the pool's
+    /// favour.
+    pub fn deposit(&self, lp: u64) -> Result<LiquidityQuote> {
+        if lp == 0 {
+            return Err(QuoteError::ZeroAmount);
+        }
+        if self.lp_supply == 0 {
+            return Err(QuoteError::NoLiquidity);
+        }
+
+        Ok(LiquidityQuote {
+            x: math::mul_div_ceil(self.reserve_x, lp, self.lp_supply)?,
+            y: math::mul_div_ceil(self.reserve_y, lp, self.lp_supply)?,
+            lp,
+        })
+    }
+
+    /// LP minted by the first deposit of `x` and `y`, after locking
+    /// `MINIMUM_LIQUIDITY`.
+    pub fn initial_deposit(x: u64, y: u64) -> Result<LiquidityQuote> {
+        if x == 0 || y == 0 {
+            return Err(QuoteError::ZeroAmount);
+        }
+
+        let liquidity = math::sqrt(x as u128 * y as u128) as u64;
+        let lp = liquidity
+            .checked_sub(MINIMUM_LIQUIDITY)
+            .filter(|lp| *lp != 0)
+            .ok_or(QuoteError::InsufficientInitialLiquidity)?;
+
+        Ok(LiquidityQuote { x, y, lp })
+    }
+
+    /// Amounts returned for burning `lp` LP tokens, rounded down in the
+    /// pool's favour.
+    pub fn withdraw(&self, lp: u64) -> Result<LiquidityQuote> {
+        if lp == 0 {
+            return Err(QuoteError::ZeroAmount);
+        }
+        if lp > self.lp_supply {
+            return Err(QuoteError::InsufficientBalance);
+        }
+
+        Ok(LiquidityQuote {
+            x: math::mul_div(self.reserve_x, lp, self.lp_supply)?,
+            y: math::mul_div(self.reserve_y, lp, self.lp_supply)?,
+            lp,
+        })
+    }
+
+    /// Single-sided deposit of `amount`: swaps the optimal portion through the
+    /// pool and adds the rest as liquidity together with the swap output.
+    /// Returns the swap leg and the LP minted.
+    pub fn deposit_single(&self, is_x: bool, amount: u64) -> Result<(SwapQuote, u64)> {
+        if self.lp_supply == 0 {
+            return Err(QuoteError::NoLiquidity);
+        }
+
+        let reserve_in = match is_x {
+            true => self.reserve_x,
+            false => self.reserve_y,
+        };
+
+        let swap_amount = constant_product::zap_swap_amount(amount, reserve_in, self.fees)?;
+        if swap_amount == 0 || swap_amount >= amount {
+            return Err(QuoteError::ZeroAmount);
+        }
+
+        let swap = self.swap(is_x, swap_amount)?;
+
+        let (post_in, post_out) = match is_x {
+            true => (self.reserve_x, self.reserve_y),
+            false => (self.reserve_y, self.reserve_x),
+        };
+        let post_in = post_in.checked_add(swap_amount).ok_or(QuoteError::Overflow)?;
+        let post_out = post_out.checked_sub(swap.withdraw).ok_or(QuoteError::Underflow)?;
+
+        // Whichever side is limiting sets the LP amount; any remainder accrues
+        // to the pool.
+        let lp_in = math::mul_div(self.lp_supply, amount - swap_amount, post_in)?;
+        let lp_out = math::mul_div(self.lp_supply, swap.withdraw, post_out)?;
+        let lp = lp_in.min(lp_out);
+
+        if lp == 0 {
+            return Err(QuoteError::ZeroAmount);
+        }
+
+        Ok((swap, lp))
+    }
+}
+
+/// Protocol share of a swap fee, `protocol_fee` being basis points of `fee`.
+pub fn protocol_fee(fee: u64, protocol_fee: u16) -> Result<u64> {
+    math::mul_div(fee, protocol_fee as u64, MAX_BPS as u64)
+}
@@ -0,0 +1,35 @@
+use crate::{QuoteError, Result};
+
+/// `a * b / c`, rounded down.
+pub fn mul_div(a: u64, b: u64, c: u64) -> Result<u64> {
+    if c == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+
+    u64::try_from(a as u128 * b as u128 / c as u128).map_err(|_| QuoteError::Overflow)
+}
+
+/// `a * b / c`, rounded up.
+pub fn mul_div_ceil(a: u64, b: u64, c: u64) -> Result<u64> {
+    if c == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+
+    u64::try_from((a as u128 * b as u128).div_ceil(c as u128)).map_err(|_| QuoteError::Overflow)
+}
+
+/// Integer square root, rounded down.
+pub fn sqrt(n: u128) -> u128 {
+    if n < 2 {
+        return n;
+    }
+
+    let mut x = n;
+    let mut y = x.div_ceil(2);
+    while y < x {
+        x = y;
+        y = (x + n / x) / 2;
+    }
+
+    x
+}
@@ -0,0 +1,133 @@
+//! Two-coin StableSwap invariant
+//! `A n^n (x + y) + D = A D n^n + D^(n+1) / (n^n x y)` with `n = 2`.
+//!
+//! All arithmetic is checked `u128`; reserves large enough to overflow it
+//! fail with `QuoteError::Overflow` rather than mispricing.
+
+use ethnum::U256;
+
+use crate::{math, QuoteError, Result, MAX_BPS};
+
+pub const MIN_AMP: u64 = 1;
+pub const MAX_AMP: u64 = 1_000_000;
+
+/// Upper bound on Newton iterations; convergence normally takes a handful.
+const MAX_ITERATIONS: usize = 256;
+
+/// Exact-in swap of `amount`. The fee is charged on the input, which stays in
+/// the pool in full. Returns `(withdraw, fee)`.
+pub fn swap(amp: u64, reserve_in: u64, reserve_out: u64, amount: u64, fees: u16) -> Result<(u64, u64)> {
+    if reserve_in == 0 || reserve_out == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+    if fees > MAX_BPS {
+        return Err(QuoteError::InvalidFee);
+    }
+
+    let amount_in = math::mul_div(amount, (MAX_BPS - fees) as u64, MAX_BPS as u64)?;
+    let fee = amount - amount_in;
+
+    let d = compute_d(amp, reserve_in as u128, reserve_out as u128)?;
+    let new_in = reserve_in as u128 + amount_in as u128;
+    let new_out = compute_y(amp, new_in, d)?;
+
+    // Round against the trader.
+    let withdraw = (reserve_out as u128)
+        .checked_sub(new_out)
+        .and_then(|out| out.checked_sub(1))
+        .ok_or(QuoteError::InsufficientBalance)?;
+
+    Ok((withdraw as u64, fee))
+}
+
+pub fn compute_d(amp: u64, x: u128, y: u128) -> Result<u128> {
+    let sum = x + y;
+    if sum == 0 {
+        return Ok(0);
+    }
+
+    let ann = amp as u128 * 2;
+    let mut d = sum;
+
+    for _ in 0..MAX_ITERATIONS {
+        let mut d_p = d;
+        d_p = d_p.checked_mul(d).ok_or(QuoteError::Overflow)? / (x * 2);
+        d_p = d_p.checked_mul(d).ok_or(QuoteError::Overflow)? / (y * 2);
+
+        let d_prev = d;
+        let numerator = ann
+            .checked_mul(sum)
+            .and_then(|n| n.checked_add(d_p * 2))
+            .and_then(|n| n.checked_mul(d))
+            .ok_or(QuoteError::Overflow)?;
+        let denominator = (ann - 1)
+            .checked_mul(d)
+            .and_then(|n| n.checked_add(d_p * 3))
+            .ok_or(QuoteError::Overflow)?;
+        d = numerator / denominator;
+
+        if d.abs_diff(d_prev) <= 1 {
+            return Ok(d);
+        }
+    }
+
+    Err(QuoteError::NotConverged)
+}
+
+/// Q64.64 marginal prices `(dy / dx, dx / dy)` at reserves `x` and `y`: the
+/// slope of the invariant, not the reserve ratio. Equal to `(1, 1)` for a
+/// balanced pool and tends to `(y / x, x / y)` as `amp` falls.
+pub fn spot_prices(amp: u64, x: u64, y: u64) -> Result<(u128, u128)> {
+    if x == 0 || y == 0 {
+        return Err(QuoteError::NoLiquidity);
+    }
+
+    let (x, y) = (x as u128, y as u128);
+    let d = U256::from(compute_d(amp, x, y)?);
+    let ann = U256::from(amp as u128 * 2);
+
+    // `D^3 / (4xy)`, rounded as in `compute_d`.
+    let d_p = d * d / U256::from(x * 2) * d / U256::from(y * 2);
+
+    // The slope is `-dF/dx / dF/dy` of the invariant `F`; both partials are
+    // scaled by `4x^2y^2 / D^3` to stay integral.
+    let (x, y) = (U256::from(x), U256::from(y));
+    let numerator = y * (ann * x + d_p);
+    let denominator = x * (ann * y + d_p);
+
+    Ok((
+        to_u128((numerator << 64) / denominator)?,
+        to_u128((denominator << 64) / numerator)?,
+    ))
+}
+
+/// Balance of the other side that keeps the invariant at `d` when one side
+/// holds `x`.
+pub fn compute_y(amp: u64, x: u128, d: u128) -> Result<u128> {
+    let ann = amp as u128 * 2;
+
+    let c = d.checked_mul(d).ok_or(QuoteError::Overflow)? / (x * 2);
+    let c = c.checked_mul(d).ok_or(QuoteError::Overflow)? / (ann * 2);
+    let b = x + d / ann;
+
+    let mut y = d;
+    for _ in 0..MAX_ITERATIONS {
+        let y_prev = y;
+        let numerator = y
+            .checked_mul(y)
+            .and_then(|n| n.checked_add(c))
+            .ok_or(QuoteError::Overflow)?;
+        let denominator = (y * 2 + b).checked_sub(d).ok_or(QuoteError::Underflow)?;
+        y = numerator / denominator;
+
+        if y.abs_diff(y_prev) <= 1 {
+            return Ok(y);
+        }
+    }
+
+    Err(QuoteError::NotConverged)
+}
+
+fn to_u128(n: U256) -> Result<u128> {
+    u128::try_from(n).map_err(|_| QuoteError::Overflow)
+}
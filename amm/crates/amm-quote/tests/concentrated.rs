@@ -0,0 +1,100 @@
+use amm_quote::concentrated::*;
+use proptest::prelude::*;
+
+proptest! {
+    #[test]
+    fn tick_and_sqrt_price_round_trip(tick in MIN_TICK..=MAX_TICK) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+
+        prop_assert_eq!(tick_at_sqrt_price(sqrt_price).unwrap(), tick);
+        if tick < MAX_TICK {
+            prop_assert!(sqrt_price_at_tick(tick + 1).unwrap() > sqrt_price);
+        }
+    }
+
+    #[test]
+    fn swap_step_stays_within_budget_and_target(
+        tick in -100_000i32..100_000,
+        distance in 1i32..10_000,
+        liquidity in 1u128..1u128 << 80,
+        amount in 1u64..1u64 << 50,
+        fees in 0u16..1_000,
+        is_x: bool,
+    ) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        let sqrt_target = match is_x {
+            true => sqrt_price_at_tick(tick - distance).unwrap(),
+            false => sqrt_price_at_tick(tick + distance).unwrap(),
+        };
+
+        if let Ok(step) = compute_swap_step(is_x, sqrt_price, sqrt_target, liquidity, amount, fees) {
+            prop_assert!(step.amount_in as u128 + step.fee as u128 <= amount as u128);
+            match is_x {
+                true => prop_assert!(sqrt_target <= step.sqrt_price_next && step.sqrt_price_next <= sqrt_price),
+                false => prop_assert!(sqrt_price <= step.sqrt_price_next && step.sqrt_price_next <= sqrt_target),
+            }
+        }
+    }
+
+    #[test]
+    fn swap_step_output_is_covered_by_input(
+        tick in -100_000i32..100_000,
+        liquidity in 1_000u128..1u128 << 80,
+        amount in 1u64..1u64 << 50,
+        is_x: bool,
+    ) {
+        // Swapping the output straight back at the new price never returns
+        // more than was put in.
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        let (there_target, back_target) = match is_x {
+            true => (MIN_SQRT_PRICE, MAX_SQRT_PRICE),
+            false => (MAX_SQRT_PRICE, MIN_SQRT_PRICE),
+        };
+
+        if let Ok(there) = compute_swap_step(is_x, sqrt_price, there_target, liquidity, amount, 0) {
+            if there.amount_out != 0 {
+                if let Ok(back) = compute_swap_step(!is_x, there.sqrt_price_next, back_target, liquidity, there.amount_out, 0) {
+                    prop_assert!(back.amount_out <= there.amount_in);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn adding_then_removing_liquidity_never_returns_more(
+        tick in -50_000i32..50_000,
+        lower in -60_000i32..60_000,
+        width in 1i32..20_000,
+        liquidity in 1u128..1u128 << 64,
+    ) {
+        let sqrt_price = sqrt_price_at_tick(tick).unwrap();
+        let upper = lower + width;
+
+        if let Ok(deposit) = amounts_for_liquidity(sqrt_price, tick, lower, upper, liquidity, true) {
+            let withdraw = amounts_for_liquidity(sqrt_price, tick, lower, upper, liquidity, false).unwrap();
+
+            prop_assert!(withdraw.0 <= deposit.0 && withdraw.1 <= deposit.1);
+        }
+    }
+}
+
+#[test]
+fn sqrt_price_bounds_match_tick_bounds() {
+    assert_eq!(sqrt_price_at_tick(MIN_TICK).unwrap(), MIN_SQRT_PRICE);
+    assert_eq!(sqrt_price_at_tick(MAX_TICK).unwrap(), MAX_SQRT_PRICE);
+    assert_eq!(sqrt_price_at_tick(0).unwrap(), 1 << 64);
+    assert!(sqrt_price_at_tick(MAX_TICK + 1).is_err());
+}
+
+#[test]
+fn range_below_price_is_all_y_and_above_is_all_x() {
+    let sqrt_price = sqrt_price_at_tick(0).unwrap();
+
+    let (x, y) = amounts_for_liquidity(sqrt_price, 0, -200, -100, 1 << 40, true).unwrap();
+    assert_eq!(x, 0);
+    assert!(y > 0);
+
+    let (x, y) = amounts_for_liquidity(sqrt_price, 0, 100, 200, 1 << 40, true).unwrap();
+    assert!(x > 0);
+    assert_eq!(y, 0);
+}
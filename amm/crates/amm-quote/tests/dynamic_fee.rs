@@ -0,0 +1,102 @@
+use amm_quote::{dynamic_fee::{price_change_bps, spot_price_change_bps, DynamicFee}, QuoteError, MAX_BPS};
+use proptest::prelude::*;
+
+fn params() -> impl Strategy<Value = DynamicFee> {
+    (0u16..=MAX_BPS, 0u16..=MAX_BPS, 1u64..1 << 40, 1i64..1 << 20).prop_map(|(a, b, max_volatility, decay_period)| {
+        DynamicFee {
+            min_fees: a.min(b),
+            max_fees: a.max(b),
+            max_volatility,
+            decay_period,
+        }
+    })
+}
+
+proptest! {
+    #[test]
+    fn fee_stays_within_bounds_and_grows_with_volatility(
+        params in params(),
+        a: u64,
+        b: u64,
+    ) {
+        let (low, high) = (a.min(b), a.max(b));
+
+        prop_assert!(params.fee(low) >= params.min_fees);
+        prop_assert!(params.fee(high) <= params.max_fees);
+        prop_assert!(params.fee(low) <= params.fee(high));
+    }
+
+    #[test]
+    fn decay_never_grows_and_reaches_zero(
+        params in params(),
+        volatility: u64,
+        a in 0i64..1 << 21,
+        b in 0i64..1 << 21,
+    ) {
+        let (earlier, later) = (a.min(b), a.max(b));
+
+        prop_assert!(params.decay(volatility, earlier) <= volatility);
+        prop_assert!(params.decay(volatility, later) <= params.decay(volatility, earlier));
+        prop_assert_eq!(params.decay(volatility, params.decay_period), 0);
+    }
+
+    #[test]
+    fn price_change_is_zero_only_below_a_basis_point(
+        x in 1u64..1 << 48,
+        y in 1u64..1 << 48,
+        dx in 0u64..1 << 40,
+    ) {
+        // y / x moving to y / (x + dx) is a move of dx / (x + dx).
+        prop_assert_eq!(price_change_bps(x, y, x, y), 0);
+        prop_assert_eq!(price_change_bps(x, y, x + dx, y) == 0, dx as u128 * 10_000 < x as u128 + dx as u128);
+    }
+}
+
+#[test]
+fn fee_interpolates_linearly() {
+    let params = DynamicFee { min_fees: 10, max_fees: 110, max_volatility: 1_000, decay_period: 60 };
+
+    assert_eq!(params.fee(0), 10);
+    assert_eq!(params.fee(500), 60);
+    assert_eq!(params.fee(1_000), 110);
+    assert_eq!(params.fee(u64::MAX), 110);
+}
+
+#[test]
+fn decay_is_linear_over_the_period() {
+    let params = DynamicFee { min_fees: 10, max_fees: 110, max_volatility: 1_000, decay_period: 60 };
+
+    assert_eq!(params.decay(600, 0), 600);
+    assert_eq!(params.decay(600, 30), 300);
+    assert_eq!(params.decay(600, 60), 0);
+    assert_eq!(params.decay(600, -5), 600);
+}
+
+#[test]
+fn price_change_measures_relative_move() {
+    // y / x goes from 4 to 4.4: a 10% move.
+    assert_eq!(price_change_bps(1_000, 4_000, 1_000, 4_400), 1_000);
+    // And from 4 to 2: a 50% move.
+    assert_eq!(price_change_bps(1_000, 4_000, 2_000, 4_000), 5_000);
+    assert_eq!(price_change_bps(0, 4_000, 1_000, 4_000), 0);
+}
+
+#[test]
+fn spot_price_change_measures_relative_move() {
+    // From 2 to 2.5: a 25% move.
+    assert_eq!(spot_price_change_bps(2 << 64, 5 << 63), 2_500);
+    // And from 4 to 2: a 50% move.
+    assert_eq!(spot_price_change_bps(4 << 64, 2 << 64), 5_000);
+    assert_eq!(spot_price_change_bps(0, 1 << 64), 0);
+}
+
+#[test]
+fn validate_rejects_inverted_or_degenerate_params() {
+    let valid = DynamicFee { min_fees: 10, max_fees: 110, max_volatility: 1_000, decay_period: 60 };
+
+    assert_eq!(valid.validate(), Ok(()));
+    assert_eq!(DynamicFee { min_fees: 200, ..valid }.validate(), Err(QuoteError::InvalidFee));
+    assert_eq!(DynamicFee { max_fees: MAX_BPS + 1, ..valid }.validate(), Err(QuoteError::InvalidFee));
+    assert_eq!(DynamicFee { max_volatility: 0, ..valid }.validate(), Err(QuoteError::InvalidFee));
+    assert_eq!(DynamicFee { decay_period: 0, ..valid }.validate(), Err(QuoteError::InvalidFee));
+}
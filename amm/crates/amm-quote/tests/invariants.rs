@@ -0,0 +1,173 @@
+use amm_quote::{constant_product, stable_swap, Curve, Pool, QuoteError, MAX_BPS};
+use proptest::prelude::*;
+
+fn pool(reserve_x: u64, reserve_y: u64, lp_supply: u64, fees: u16, curve: Curve) -> Pool {
+    Pool {
+        reserve_x,
+        reserve_y,
+        lp_supply,
+        fees,
+        protocol_fee: 0,
+        curve,
+    }
+}
+
+proptest! {
+    #[test]
+    fn constant_product_swap_never_decreases_k(
+        reserve_x in 1_000u64..1u64 << 48,
+        reserve_y in 1_000u64..1u64 << 48,
+        amount in 1u64..1u64 << 40,
+        fees in 0u16..1_000,
+        is_x: bool,
+    ) {
+        let pool = pool(reserve_x, reserve_y, 1, fees, Curve::ConstantProduct);
+
+        if let Ok(quote) = pool.swap(is_x, amount) {
+            let (x, y) = match is_x {
+                true => (reserve_x + quote.deposit, reserve_y - quote.withdraw),
+                false => (reserve_x - quote.withdraw, reserve_y + quote.deposit),
+            };
+            prop_assert!(x as u128 * y as u128 >= reserve_x as u128 * reserve_y as u128);
+        }
+    }
+
+    #[test]
+    fn constant_product_swap_matches_closed_form(
+        reserve_in in 1u64..1u64 << 48,
+        reserve_out in 1u64..1u64 << 48,
+        amount in 1u64..1u64 << 40,
+        fees in 0u16..1_000,
+    ) {
+        let (withdraw, fee) = constant_product::swap(reserve_in, reserve_out, amount, fees).unwrap();
+
+        let amount_in = amount as u128 * (MAX_BPS - fees) as u128 / MAX_BPS as u128;
+        let expected = reserve_out as u128 * amount_in / (reserve_in as u128 + amount_in);
+
+        prop_assert_eq!(withdraw as u128, expected);
+        prop_assert_eq!(fee as u128, amount as u128 - amount_in);
+    }
+
+    #[test]
+    fn stable_swap_output_is_bounded_by_reserve(
+        reserve_x in 1_000_000u64..1u64 << 40,
+        reserve_y in 1_000_000u64..1u64 << 40,
+        amount in 1u64..1u64 << 36,
+        amp in 1u64..10_000,
+        is_x: bool,
+    ) {
+        let pool = pool(reserve_x, reserve_y, 1, 4, Curve::StableSwap { amp });
+
+        if let Ok(quote) = pool.swap(is_x, amount) {
+            let reserve_out = if is_x { reserve_y } else { reserve_x };
+            prop_assert!(quote.withdraw < reserve_out);
+        }
+    }
+
+    #[test]
+    fn stable_swap_spot_price_lies_between_par_and_reserve_ratio(
+        reserve_x in 1_000_000u64..1u64 << 40,
+        reserve_y in 1_000_000u64..1u64 << 40,
+        amp in 1u64..10_000,
+    ) {
+        let (price_x, price_y) = stable_swap::spot_prices(amp, reserve_x, reserve_y).unwrap();
+        let ratio = ((reserve_y as u128) << 64) / reserve_x as u128;
+        let (low, high) = (ratio.min(1 << 64), ratio.max(1 << 64));
+
+        // One unit of slack for the rounding in `D` and the divisions.
+        prop_assert!(price_x + 1 >= low && price_x <= high + 1);
+        prop_assert_eq!(price_x >= 1 << 64, price_y <= 1 << 64);
+    }
+
+    #[test]
+    fn deposit_then_withdraw_never_profits(
+        reserve_x in 1_000u64..1u64 << 48,
+        reserve_y in 1_000u64..1u64 << 48,
+        lp_supply in 1_000u64..1u64 << 48,
+        lp in 1u64..1u64 << 40,
+    ) {
+        let before = pool(reserve_x, reserve_y, lp_supply, 30, Curve::ConstantProduct);
+        let deposit = before.deposit(lp).unwrap();
+
+        let after = Pool {
+            reserve_x: reserve_x + deposit.x,
+            reserve_y: reserve_y + deposit.y,
+            lp_supply: lp_supply + lp,
+            ..before
+        };
+        let withdraw = after.withdraw(lp).unwrap();
+
+        prop_assert!(withdraw.x <= deposit.x);
+        prop_assert!(withdraw.y <= deposit.y);
+    }
+
+    #[test]
+    fn withdraw_is_proportional(
+        reserve_x in 0u64..1u64 << 48,
+        reserve_y in 0u64..1u64 << 48,
+        lp_supply in 1u64..1u64 << 48,
+        share in 1u64..=10_000,
+    ) {
+        let pool = pool(reserve_x, reserve_y, lp_supply, 30, Curve::ConstantProduct);
+        let lp = (lp_supply as u128 * share as u128 / 10_000).max(1) as u64;
+        let quote = pool.withdraw(lp).unwrap();
+
+        prop_assert_eq!(quote.x as u128, reserve_x as u128 * lp as u128 / lp_supply as u128);
+        prop_assert_eq!(quote.y as u128, reserve_y as u128 * lp as u128 / lp_supply as u128);
+    }
+
+    #[test]
+    fn single_sided_deposit_is_worth_at_most_its_input(
+        reserve_x in 1_000_000u64..1u64 << 48,
+        reserve_y in 1_000_000u64..1u64 << 48,
+        lp_supply in 1_000_000u64..1u64 << 48,
+        amount in 1_000u64..1u64 << 36,
+    ) {
+        let pool = pool(reserve_x, reserve_y, lp_supply, 30, Curve::ConstantProduct);
+
+        if let Ok((swap, lp)) = pool.deposit_single(true, amount) {
+            let after = Pool {
+                reserve_x: reserve_x + amount,
+                reserve_y,
+                lp_supply: lp_supply + lp,
+                ..pool
+            };
+            let withdraw = after.withdraw(lp).unwrap();
+
+            prop_assert!(withdraw.x <= amount - swap.deposit);
+            prop_assert!(withdraw.y <= swap.withdraw);
+        }
+    }
+}
+
+#[test]
+fn stable_swap_spot_price_is_the_marginal_rate() {
+    assert_eq!(stable_swap::spot_prices(100, 1_000_000_000, 1_000_000_000), Ok((1 << 64, 1 << 64)));
+    assert_eq!(stable_swap::spot_prices(100, 0, 1_000_000_000), Err(QuoteError::NoLiquidity));
+
+    // A small fee-free trade on an imbalanced pool fills at the spot price.
+    let (reserve_x, reserve_y) = (1_000_000_000_000, 4_000_000_000_000);
+    let (price_x, _) = stable_swap::spot_prices(100, reserve_x, reserve_y).unwrap();
+    let (withdraw, _) = stable_swap::swap(100, reserve_x, reserve_y, 1_000_000, 0).unwrap();
+
+    let rate = ((withdraw as u128) << 64) / 1_000_000;
+    assert!(price_x > rate && price_x - rate < price_x / 10_000);
+    assert!(price_x < ((reserve_y as u128) << 64) / reserve_x as u128);
+}
+
+#[test]
+fn initial_deposit_locks_minimum_liquidity() {
+    let quote = Pool::initial_deposit(50_000_000, 50_000_000).unwrap();
+    assert_eq!(quote.lp, 50_000_000 - amm_quote::MINIMUM_LIQUIDITY);
+
+    assert_eq!(
+        Pool::initial_deposit(1_000, 1_000),
+        Err(QuoteError::InsufficientInitialLiquidity)
+    );
+}
+
+#[test]
+fn swap_against_empty_pool_fails() {
+    let pool = pool(0, 0, 0, 30, Curve::ConstantProduct);
+    assert_eq!(pool.swap(true, 1_000), Err(QuoteError::NoLiquidity));
+}
@@ -0,0 +1,171 @@
+//! Random sequences of deposits, swaps and withdrawals driven through the
+//! same calls the program makes, checking the pool invariants after every
+//! step.
+
+use amm_quote::{Curve, Pool, MINIMUM_LIQUIDITY};
+use ethnum::U256;
+use proptest::prelude::*;
+
+#[derive(Clone, Debug)]
+enum Action {
+    Deposit { lp: u64 },
+    DepositSingle { is_x: bool, amount: u64 },
+    Swap { is_x: bool, amount: u64 },
+    /// Basis points of the provider's LP balance.
+    Withdraw { share: u16 },
+    /// Deposit immediately followed by withdrawing the LP it minted.
+    RoundTrip { lp: u64 },
+}
+
+fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        (1u64..1 << 34).prop_map(|lp| Action::Deposit { lp }),
+        (any::<bool>(), 1u64..1 << 34).prop_map(|(is_x, amount)| Action::DepositSingle { is_x, amount }),
+        (any::<bool>(), 1u64..1 << 34).prop_map(|(is_x, amount)| Action::Swap { is_x, amount }),
+        (1u16..=10_000).prop_map(|share| Action::Withdraw { share }),
+        (1u64..1 << 34).prop_map(|lp| Action::RoundTrip { lp }),
+    ]
+}
+
+/// `sqrt(x * y) / lp` compared without the square root: true when `after`
+/// backs every LP token with at least as much as `before`.
+fn share_value_held(before: &Pool, after: &Pool) -> bool {
+    let k = |pool: &Pool| U256::from(pool.reserve_x) * U256::from(pool.reserve_y);
+    let lp = |pool: &Pool| U256::from(pool.lp_supply) * U256::from(pool.lp_supply);
+
+    k(after) * lp(before) >= k(before) * lp(after)
+}
+
+/// Both reserves per LP token are at least what they were.
+fn reserves_per_share_held(before: &Pool, after: &Pool) -> bool {
+    after.reserve_x as u128 * before.lp_supply as u128 >= before.reserve_x as u128 * after.lp_supply as u128
+        && after.reserve_y as u128 * before.lp_supply as u128 >= before.reserve_y as u128 * after.lp_supply as u128
+}
+
+fn run(initial_x: u64, initial_y: u64, fees: u16, curve: Curve, actions: Vec<Action>) -> Result<(), TestCaseError> {
+    let first = match Pool::initial_deposit(initial_x, initial_y) {
+        Ok(quote) => quote,
+        Err(_) => return Ok(()),
+    };
+
+    let mut pool = Pool {
+        reserve_x: initial_x,
+        reserve_y: initial_y,
+        lp_supply: first.lp + MINIMUM_LIQUIDITY,
+        fees,
+        protocol_fee: 0,
+        curve,
+    };
+    let mut lp_balance = first.lp;
+
+    for action in actions {
+        let before = pool;
+
+        match action {
+            Action::Deposit { lp } => {
+                let Ok(quote) = pool.deposit(lp) else { continue };
+                pool.reserve_x += quote.x;
+                pool.reserve_y += quote.y;
+                pool.lp_supply += quote.lp;
+                lp_balance += quote.lp;
+
+                prop_assert!(reserves_per_share_held(&before, &pool));
+            }
+            Action::DepositSingle { is_x, amount } => {
+                let Ok((_, lp)) = pool.deposit_single(is_x, amount) else { continue };
+                // The swap leg's output never leaves the vault, so the whole
+                // input is added to one side.
+                match is_x {
+                    true => pool.reserve_x += amount,
+                    false => pool.reserve_y += amount,
+                }
+                pool.lp_supply += lp;
+                lp_balance += lp;
+            }
+            Action::Swap { is_x, amount } => {
+                let Ok(quote) = pool.swap(is_x, amount) else { continue };
+                match is_x {
+                    true => {
+                        pool.reserve_x += quote.deposit;
+                        pool.reserve_y -= quote.withdraw;
+                    }
+                    false => {
+                        pool.reserve_y += quote.deposit;
+                        pool.reserve_x -= quote.withdraw;
+                    }
+                }
+
+                if curve == Curve::ConstantProduct {
+                    prop_assert!(
+                        pool.reserve_x as u128 * pool.reserve_y as u128
+                            >= before.reserve_x as u128 * before.reserve_y as u128
+                    );
+                }
+            }
+            Action::Withdraw { share } => {
+                let lp = (lp_balance as u128 * share as u128 / 10_000) as u64;
+                let Ok(quote) = pool.withdraw(lp) else { continue };
+                pool.reserve_x -= quote.x;
+                pool.reserve_y -= quote.y;
+                pool.lp_supply -= quote.lp;
+                lp_balance -= quote.lp;
+
+                prop_assert!(reserves_per_share_held(&before, &pool));
+            }
+            Action::RoundTrip { lp } => {
+                let Ok(deposit) = pool.deposit(lp) else { continue };
+                let after_deposit = Pool {
+                    reserve_x: pool.reserve_x + deposit.x,
+                    reserve_y: pool.reserve_y + deposit.y,
+                    lp_supply: pool.lp_supply + lp,
+                    ..pool
+                };
+                let withdraw = after_deposit.withdraw(lp).unwrap();
+
+                prop_assert!(withdraw.x <= deposit.x && withdraw.y <= deposit.y);
+                pool.reserve_x = after_deposit.reserve_x - withdraw.x;
+                pool.reserve_y = after_deposit.reserve_y - withdraw.y;
+            }
+        }
+
+        if curve == Curve::ConstantProduct {
+            prop_assert!(share_value_held(&before, &pool));
+        }
+        prop_assert!(pool.lp_supply >= MINIMUM_LIQUIDITY + lp_balance);
+    }
+
+    // Whoever is left can always exit, and the locked liquidity keeps both
+    // reserves funded.
+    if lp_balance != 0 {
+        let quote = pool.withdraw(lp_balance).unwrap();
+        pool.reserve_x -= quote.x;
+        pool.reserve_y -= quote.y;
+        pool.lp_supply -= lp_balance;
+    }
+    prop_assert_eq!(pool.lp_supply, MINIMUM_LIQUIDITY);
+    prop_assert!(pool.reserve_x != 0 && pool.reserve_y != 0);
+
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    fn constant_product_sequences_hold_invariants(
+        initial_x in 1_000_000u64..1 << 36,
+        initial_y in 1_000_000u64..1 << 36,
+        fees in 0u16..1_000,
+        actions in prop::collection::vec(action(), 1..40),
+    ) {
+        run(initial_x, initial_y, fees, Curve::ConstantProduct, actions)?;
+    }
+
+    #[test]
+    fn stable_swap_sequences_hold_invariants(
+        initial_x in 1_000_000u64..1 << 36,
+        initial_y in 1_000_000u64..1 << 36,
+        amp in 1u64..10_000,
+        actions in prop::collection::vec(action(), 1..40),
+    ) {
+        run(initial_x, initial_y, 4, Curve::StableSwap { amp }, actions)?;
+    }
+}
@@ -0,0 +1,798 @@
+//! Runs the compiled amm program inside LiteSVM. `anchor build` must have
+//! produced `target/deploy/amm.so` first; everything else happens in-process.
+
+use std::collections::HashMap;
+
+use amm::{accounts, error::AmmError, instruction, state::{ConcentratedPool, Config, CurveType, DynamicFeeParams, PoolRegistry}};
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::{
+    associated_token::{self, get_associated_token_address_with_program_id, spl_associated_token_account},
+    token,
+    token_2022::{
+        self,
+        spl_token_2022::{
+            extension::{transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType, StateWithExtensions},
+            instruction::{initialize_mint2, mint_to, transfer_checked},
+            state::{Account as TokenAccount, Mint},
+        },
+    },
+};
+use litesvm::{types::TransactionResult, LiteSVM};
+use proptest::{prelude::{any, Strategy}, prop_oneof};
+use solana_sdk::{
+    account::Account,
+    clock::Clock,
+    instruction::{AccountMeta, Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::Keypair,
+    signer::Signer,
+    system_program,
+    sysvar,
+    transaction::{Transaction, TransactionError},
+};
+
+pub const PROGRAM_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../target/deploy/amm.so");
+
+/// Tokens minted to the user on each side of the pair.
+pub const USER_BALANCE: u64 = 1_000_000_000_000;
+
+/// Decimals of every mint the fixture creates.
+pub const DECIMALS: u8 = 6;
+
+/// Token program, and extensions, of a mint created by the fixture.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MintKind {
+    Token,
+    Token2022,
+    /// Token-2022 mint withholding `bps` basis points of every transfer,
+    /// with no maximum fee.
+    TransferFee { bps: u16 },
+}
+
+impl MintKind {
+    pub fn token_program(&self) -> Pubkey {
+        match self {
+            MintKind::Token => token::ID,
+            MintKind::Token2022 | MintKind::TransferFee { .. } => token_2022::ID,
+        }
+    }
+}
+
+/// A constant-product pool on two fresh mints, with `authority` owning the
+/// pool and `user` holding `USER_BALANCE` of both tokens.
+pub struct Fixture {
+    pub svm: LiteSVM,
+    pub authority: Keypair,
+    pub user: Keypair,
+    pub seed: u64,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub mint_lp: Pubkey,
+    pub config: Pubkey,
+    /// Owning program of every mint created by the fixture. Anything else,
+    /// such as LP mints, belongs to the legacy token program.
+    token_programs: HashMap<Pubkey, Pubkey>,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        Self::with_mints(MintKind::Token, MintKind::Token)
+    }
+
+    pub fn with_mints(kind_x: MintKind, kind_y: MintKind) -> Self {
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(amm::ID, PROGRAM_PATH)
+            .expect("missing target/deploy/amm.so, run `anchor build` first");
+
+        let authority = Keypair::new();
+        let user = Keypair::new();
+        svm.airdrop(&authority.pubkey(), 10_000_000_000).unwrap();
+        svm.airdrop(&user.pubkey(), 10_000_000_000).unwrap();
+
+        let mint_x = funded_mint(&mut svm, &authority, &user, kind_x);
+        let mint_y = funded_mint(&mut svm, &authority, &user, kind_y);
+        let pool = PoolKeys::new(42, mint_x, mint_y);
+
+        Self {
+            svm,
+            authority,
+            user,
+            seed: pool.seed,
+            mint_x,
+            mint_y,
+            mint_lp: pool.mint_lp,
+            config: pool.config,
+            token_programs: HashMap::from([
+                (mint_x, kind_x.token_program()),
+                (mint_y, kind_y.token_program()),
+            ]),
+        }
+    }
+
+    /// The fixture's own pool.
+    pub fn pool(&self) -> PoolKeys {
+        PoolKeys::new(self.seed, self.mint_x, self.mint_y)
+    }
+
+    /// A fresh legacy mint with `USER_BALANCE` minted to the user.
+    pub fn create_mint(&mut self) -> Pubkey {
+        let mint = funded_mint(&mut self.svm, &self.authority, &self.user, MintKind::Token);
+        self.token_programs.insert(mint, token::ID);
+
+        mint
+    }
+
+    pub fn token_program(&self, mint: &Pubkey) -> Pubkey {
+        self.token_programs.get(mint).copied().unwrap_or(token::ID)
+    }
+
+    /// `owner`'s associated token account for `mint`.
+    pub fn ata(&self, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(owner, mint, &self.token_program(mint))
+    }
+
+    pub fn balance(&self, owner: &Pubkey, mint: &Pubkey) -> u64 {
+        self.svm
+            .get_account(&self.ata(owner, mint))
+            .and_then(|account| StateWithExtensions::<TokenAccount>::unpack(&account.data).map(|state| state.base.amount).ok())
+            .unwrap_or(0)
+    }
+
+    pub fn user_balance(&self, mint: &Pubkey) -> u64 {
+        self.balance(&self.user.pubkey(), mint)
+    }
+
+    pub fn vault_balance(&self, mint: &Pubkey) -> u64 {
+        self.balance(&self.config, mint)
+    }
+
+    pub fn lp_supply(&self) -> u64 {
+        self.svm
+            .get_account(&self.mint_lp)
+            .and_then(|account| StateWithExtensions::<Mint>::unpack(&account.data).map(|state| state.base.supply).ok())
+            .unwrap_or(0)
+    }
+
+    pub fn config_account(&self) -> Config {
+        let account = self.svm.get_account(&self.config).unwrap();
+
+        Config::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    pub fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    /// Moves the clock forward by `seconds`.
+    pub fn warp(&mut self, seconds: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        self.svm.set_sysvar::<Clock>(&clock);
+    }
+
+    pub fn initialize(&mut self, fees: u16) -> TransactionResult {
+        self.initialize_pool(&self.pool(), fees, CurveType::ConstantProduct, 0)
+    }
+
+    pub fn initialize_pool(&mut self, pool: &PoolKeys, fees: u16, curve: CurveType, amp: u64) -> TransactionResult {
+        self.initialize_with_registry(pool, fees, curve, amp, None)
+    }
+
+    /// Initializes `pool` and claims its registry entry in the same
+    /// instruction, returning the registry address.
+    pub fn initialize_registered(&mut self, pool: &PoolKeys, fees: u16, curve: CurveType, amp: u64) -> (Pubkey, TransactionResult) {
+        let registry = PoolRegistry::find_address(&pool.mint_x, &pool.mint_y, fees, curve).0;
+
+        (registry, self.initialize_with_registry(pool, fees, curve, amp, Some(registry)))
+    }
+
+    fn initialize_with_registry(
+        &mut self,
+        pool: &PoolKeys,
+        fees: u16,
+        curve: CurveType,
+        amp: u64,
+        registry: Option<Pubkey>,
+    ) -> TransactionResult {
+        let accounts = accounts::Initialize {
+            initializer: self.authority.pubkey(),
+            mint_x: pool.mint_x,
+            mint_y: pool.mint_y,
+            mint_lp: pool.mint_lp,
+            vault_x: self.ata(&pool.config, &pool.mint_x),
+            vault_y: self.ata(&pool.config, &pool.mint_y),
+            vault_lp: self.ata(&pool.config, &pool.mint_lp),
+            config: pool.config,
+            registry,
+            associated_token_program: associated_token::ID,
+            token_program_x: self.token_program(&pool.mint_x),
+            token_program_y: self.token_program(&pool.mint_y),
+            token_program_lp: self.token_program(&pool.mint_lp),
+            system_program: system_program::ID,
+        };
+        let data = instruction::Initialize {
+            seed: pool.seed,
+            fees,
+            lp_decimals: DECIMALS,
+            authority: Some(self.authority.pubkey()),
+            curve,
+            amp,
+        };
+
+        self.send(accounts, data, Payer::Authority)
+    }
+
+    /// Registers the fixture's pool, returning the registry address.
+    pub fn register_pool(&mut self) -> (Pubkey, TransactionResult) {
+        let config = self.config_account();
+        let registry = PoolRegistry::find_address(&config.mint_x, &config.mint_y, config.fees, config.curve).0;
+        let accounts = accounts::RegisterPool {
+            payer: self.user.pubkey(),
+            config: self.config,
+            registry,
+            system_program: system_program::ID,
+        };
+
+        (registry, self.send(accounts, instruction::RegisterPool {}, Payer::User))
+    }
+
+    pub fn deposit(&mut self, amount: u64, max_x: u64, max_y: u64) -> TransactionResult {
+        self.deposit_with_proof(amount, max_x, max_y, Vec::new())
+    }
+
+    pub fn deposit_with_proof(&mut self, amount: u64, max_x: u64, max_y: u64, proof: Vec<[u8; 32]>) -> TransactionResult {
+        self.deposit_pool(&self.pool(), amount, max_x, max_y, proof)
+    }
+
+    pub fn deposit_pool(
+        &mut self,
+        pool: &PoolKeys,
+        amount: u64,
+        max_x: u64,
+        max_y: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> TransactionResult {
+        let ix = self.deposit_ix(pool, amount, max_x, max_y, proof);
+
+        self.send_instructions(&[ix], Payer::User)
+    }
+
+    pub fn deposit_ix(&self, pool: &PoolKeys, amount: u64, max_x: u64, max_y: u64, proof: Vec<[u8; 32]>) -> Instruction {
+        amm_instruction(self.deposit_accounts(pool), instruction::Deposit { amount, max_x, max_y, proof })
+    }
+
+    pub fn deposit_single(&mut self, is_x: bool, amount: u64, min_lp_out: u64) -> TransactionResult {
+        let accounts = self.deposit_accounts(&self.pool());
+        let data = instruction::DepositSingle { is_x, amount, min_lp_out, proof: Vec::new() };
+
+        self.send(accounts, data, Payer::User)
+    }
+
+    fn deposit_accounts(&self, pool: &PoolKeys) -> accounts::Deposit {
+        let user = self.user.pubkey();
+
+        accounts::Deposit {
+            user,
+            mint_x: pool.mint_x,
+            mint_y: pool.mint_y,
+            mint_lp: pool.mint_lp,
+            vault_x: self.ata(&pool.config, &pool.mint_x),
+            vault_y: self.ata(&pool.config, &pool.mint_y),
+            vault_lp: self.ata(&pool.config, &pool.mint_lp),
+            user_x: self.ata(&user, &pool.mint_x),
+            user_y: self.ata(&user, &pool.mint_y),
+            config: pool.config,
+            user_lp: self.ata(&user, &pool.mint_lp),
+            associated_token_program: associated_token::ID,
+            token_program_x: self.token_program(&pool.mint_x),
+            token_program_y: self.token_program(&pool.mint_y),
+            token_program_lp: self.token_program(&pool.mint_lp),
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn swap(&mut self, is_x: bool, amount: u64, min_out: u64) -> TransactionResult {
+        let ix = self.swap_ix(is_x, amount, min_out);
+
+        self.send_instructions(&[ix], Payer::User)
+    }
+
+    pub fn swap_ix(&self, is_x: bool, amount: u64, min_out: u64) -> Instruction {
+        let user = self.user.pubkey();
+        let accounts = accounts::Swap {
+            user,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            mint_lp: self.mint_lp,
+            vault_x: self.ata(&self.config, &self.mint_x),
+            vault_y: self.ata(&self.config, &self.mint_y),
+            user_x: self.ata(&user, &self.mint_x),
+            user_y: self.ata(&user, &self.mint_y),
+            config: self.config,
+            associated_token_program: associated_token::ID,
+            token_program_x: self.token_program(&self.mint_x),
+            token_program_y: self.token_program(&self.mint_y),
+            system_program: system_program::ID,
+        };
+
+        amm_instruction(accounts, instruction::Swap { is_x, amount, min_out })
+    }
+
+    /// Swaps `amount` along `hops`, each a pool and whether it is entered on
+    /// its X side.
+    pub fn swap_route(&mut self, hops: &[(PoolKeys, bool)], amount: u64, min_out: u64) -> TransactionResult {
+        let user = self.user.pubkey();
+        let mut metas = accounts::SwapRoute { user }.to_account_metas(None);
+
+        for (pool, is_x) in hops {
+            let (mint_in, mint_out) = match is_x {
+                true => (pool.mint_x, pool.mint_y),
+                false => (pool.mint_y, pool.mint_x),
+            };
+            metas.extend([
+                AccountMeta::new(pool.config, false),
+                AccountMeta::new_readonly(pool.mint_lp, false),
+                AccountMeta::new_readonly(mint_in, false),
+                AccountMeta::new_readonly(mint_out, false),
+                AccountMeta::new(self.ata(&pool.config, &mint_in), false),
+                AccountMeta::new(self.ata(&pool.config, &mint_out), false),
+                AccountMeta::new(self.ata(&user, &mint_in), false),
+                AccountMeta::new(self.ata(&user, &mint_out), false),
+                AccountMeta::new_readonly(self.token_program(&mint_in), false),
+                AccountMeta::new_readonly(self.token_program(&mint_out), false),
+            ]);
+        }
+
+        let ix = Instruction {
+            program_id: amm::ID,
+            accounts: metas,
+            data: instruction::SwapRoute { amount, min_out }.data(),
+        };
+
+        self.send_instructions(&[ix], Payer::User)
+    }
+
+    pub fn withdraw(&mut self, amount: u64, min_x: u64, min_y: u64) -> TransactionResult {
+        let ix = self.withdraw_ix(amount, min_x, min_y);
+
+        self.send_instructions(&[ix], Payer::User)
+    }
+
+    pub fn withdraw_ix(&self, amount: u64, min_x: u64, min_y: u64) -> Instruction {
+        let user = self.user.pubkey();
+        let accounts = accounts::Withdraw {
+            user,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            mint_lp: self.mint_lp,
+            vault_x: self.ata(&self.config, &self.mint_x),
+            vault_y: self.ata(&self.config, &self.mint_y),
+            user_x: self.ata(&user, &self.mint_x),
+            user_y: self.ata(&user, &self.mint_y),
+            config: self.config,
+            user_lp: self.ata(&user, &self.mint_lp),
+            associated_token_program: associated_token::ID,
+            token_program_x: self.token_program(&self.mint_x),
+            token_program_y: self.token_program(&self.mint_y),
+            token_program_lp: self.token_program(&self.mint_lp),
+            system_program: system_program::ID,
+        };
+
+        amm_instruction(accounts, instruction::Withdraw { amount, min_x, min_y })
+    }
+
+    pub fn flash_borrow_ix(&self, pool: &PoolKeys, mint: &Pubkey, amount: u64) -> Instruction {
+        amm_instruction(self.flash_loan_accounts(pool, mint), instruction::FlashBorrow { amount })
+    }
+
+    pub fn flash_repay_ix(&self, pool: &PoolKeys, mint: &Pubkey) -> Instruction {
+        amm_instruction(self.flash_loan_accounts(pool, mint), instruction::FlashRepay {})
+    }
+
+    fn flash_loan_accounts(&self, pool: &PoolKeys, mint: &Pubkey) -> accounts::FlashLoan {
+        let user = self.user.pubkey();
+        let other_mint = match *mint == pool.mint_x {
+            true => &pool.mint_y,
+            false => &pool.mint_x,
+        };
+
+        accounts::FlashLoan {
+            user,
+            mint: *mint,
+            vault: self.ata(&pool.config, mint),
+            user_token: self.ata(&user, mint),
+            config: pool.config,
+            mint_lp: pool.mint_lp,
+            other_vault: self.ata(&pool.config, other_mint),
+            instructions: sysvar::instructions::ID,
+            associated_token_program: associated_token::ID,
+            token_program: self.token_program(mint),
+            system_program: system_program::ID,
+        }
+    }
+
+    pub fn lock(&mut self) -> TransactionResult {
+        self.update_config(instruction::Lock {}, Payer::Authority)
+    }
+
+    pub fn unlock(&mut self) -> TransactionResult {
+        self.update_config(instruction::Unlock {}, Payer::Authority)
+    }
+
+    pub fn set_deposit_caps(&mut self, max_reserve_x: u64, max_reserve_y: u64) -> TransactionResult {
+        self.update_config(instruction::SetDepositCaps { max_reserve_x, max_reserve_y }, Payer::Authority)
+    }
+
+    pub fn set_lp_allowlist(&mut self, root: [u8; 32]) -> TransactionResult {
+        self.update_config(instruction::SetLpAllowlist { root }, Payer::Authority)
+    }
+
+    pub fn remove_lp_allowlist(&mut self) -> TransactionResult {
+        self.update_config(instruction::RemoveLpAllowlist {}, Payer::Authority)
+    }
+
+    pub fn set_dynamic_fee(&mut self, params: DynamicFeeParams) -> TransactionResult {
+        self.update_config(instruction::SetDynamicFee { params }, Payer::Authority)
+    }
+
+    pub fn disable_dynamic_fee(&mut self) -> TransactionResult {
+        self.update_config(instruction::DisableDynamicFee {}, Payer::Authority)
+    }
+
+    pub fn collect_protocol_fees(&mut self, treasury: &Pubkey) -> TransactionResult {
+        let accounts = accounts::CollectProtocolFees {
+            authority: self.authority.pubkey(),
+            treasury: *treasury,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            vault_x: self.ata(&self.config, &self.mint_x),
+            vault_y: self.ata(&self.config, &self.mint_y),
+            treasury_x: self.ata(treasury, &self.mint_x),
+            treasury_y: self.ata(treasury, &self.mint_y),
+            config: self.config,
+            associated_token_program: associated_token::ID,
+            token_program_x: self.token_program(&self.mint_x),
+            token_program_y: self.token_program(&self.mint_y),
+            system_program: system_program::ID,
+        };
+
+        self.send(accounts, instruction::CollectProtocolFees {}, Payer::Authority)
+    }
+
+    /// Opens a concentrated pool on the fixture's mints at a price of one,
+    /// returning its address.
+    pub fn initialize_concentrated(
+        &mut self,
+        seed: u64,
+        tick_spacing: u16,
+        min_position_liquidity: u128,
+    ) -> (Pubkey, TransactionResult) {
+        let pool = Pubkey::find_program_address(&[b"concentrated", &seed.to_le_bytes()], &amm::ID).0;
+        let accounts = accounts::InitializeConcentrated {
+            initializer: self.authority.pubkey(),
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            vault_x: self.ata(&pool, &self.mint_x),
+            vault_y: self.ata(&pool, &self.mint_y),
+            pool,
+            associated_token_program: associated_token::ID,
+            token_program_x: self.token_program(&self.mint_x),
+            token_program_y: self.token_program(&self.mint_y),
+            system_program: system_program::ID,
+        };
+        let data = instruction::InitializeConcentrated {
+            seed,
+            fees: 30,
+            tick_spacing,
+            min_position_liquidity,
+            sqrt_price: 1 << 64,
+        };
+
+        (pool, self.send(accounts, data, Payer::Authority))
+    }
+
+    /// Opens a position for the user on `pool`, returning its address.
+    pub fn open_position(&mut self, pool: &Pubkey, tick_lower: i32, tick_upper: i32) -> (Pubkey, TransactionResult) {
+        let owner = self.user.pubkey();
+        let position = Pubkey::find_program_address(
+            &[
+                b"position",
+                pool.as_ref(),
+                owner.as_ref(),
+                &tick_lower.to_le_bytes(),
+                &tick_upper.to_le_bytes(),
+            ],
+            &amm::ID,
+        ).0;
+        let accounts = accounts::OpenPosition {
+            owner,
+            pool: *pool,
+            position,
+            system_program: system_program::ID,
+        };
+
+        (position, self.send(accounts, instruction::OpenPosition { tick_lower, tick_upper }, Payer::User))
+    }
+
+    pub fn increase_liquidity(&mut self, pool: &Pubkey, position: &Pubkey, liquidity: u128) -> TransactionResult {
+        let accounts = self.modify_position_accounts(pool, position);
+        let data = instruction::IncreaseLiquidity { liquidity, max_x: u64::MAX, max_y: u64::MAX };
+
+        self.send(accounts, data, Payer::User)
+    }
+
+    pub fn decrease_liquidity(&mut self, pool: &Pubkey, position: &Pubkey, liquidity: u128) -> TransactionResult {
+        let accounts = self.modify_position_accounts(pool, position);
+        let data = instruction::DecreaseLiquidity { liquidity, min_x: 0, min_y: 0 };
+
+        self.send(accounts, data, Payer::User)
+    }
+
+    pub fn collect_fees(&mut self, pool: &Pubkey, position: &Pubkey) -> TransactionResult {
+        let accounts = self.modify_position_accounts(pool, position);
+
+        self.send(accounts, instruction::CollectFees {}, Payer::User)
+    }
+
+    pub fn close_position(&mut self, pool: &Pubkey, position: &Pubkey) -> TransactionResult {
+        let accounts = accounts::ClosePosition {
+            owner: self.user.pubkey(),
+            pool: *pool,
+            position: *position,
+        };
+
+        self.send(accounts, instruction::ClosePosition {}, Payer::User)
+    }
+
+    pub fn swap_concentrated(&mut self, pool: &Pubkey, is_x: bool, amount: u64, min_out: u64) -> TransactionResult {
+        let user = self.user.pubkey();
+        let accounts = accounts::SwapConcentrated {
+            user,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            vault_x: self.ata(pool, &self.mint_x),
+            vault_y: self.ata(pool, &self.mint_y),
+            user_x: self.ata(&user, &self.mint_x),
+            user_y: self.ata(&user, &self.mint_y),
+            pool: *pool,
+            associated_token_program: associated_token::ID,
+            token_program_x: self.token_program(&self.mint_x),
+            token_program_y: self.token_program(&self.mint_y),
+            system_program: system_program::ID,
+        };
+
+        self.send(accounts, instruction::SwapConcentrated { is_x, amount, min_out }, Payer::User)
+    }
+
+    pub fn concentrated_pool(&self, pool: &Pubkey) -> ConcentratedPool {
+        let account = self.svm.get_account(pool).unwrap();
+
+        ConcentratedPool::try_deserialize(&mut account.data.as_slice()).unwrap()
+    }
+
+    fn modify_position_accounts(&self, pool: &Pubkey, position: &Pubkey) -> accounts::ModifyPosition {
+        let owner = self.user.pubkey();
+
+        accounts::ModifyPosition {
+            owner,
+            mint_x: self.mint_x,
+            mint_y: self.mint_y,
+            pool: *pool,
+            position: *position,
+            vault_x: self.ata(pool, &self.mint_x),
+            vault_y: self.ata(pool, &self.mint_y),
+            user_x: self.ata(&owner, &self.mint_x),
+            user_y: self.ata(&owner, &self.mint_y),
+            associated_token_program: associated_token::ID,
+            token_program_x: self.token_program(&self.mint_x),
+            token_program_y: self.token_program(&self.mint_y),
+            system_program: system_program::ID,
+        }
+    }
+
+    /// Sends `amount` of `mint` from the user's account straight to
+    /// `destination`, bypassing the program.
+    pub fn transfer(&mut self, mint: &Pubkey, destination: &Pubkey, amount: u64) -> TransactionResult {
+        let user = self.user.pubkey();
+        let ix = transfer_checked(
+            &self.token_program(mint),
+            &self.ata(&user, mint),
+            mint,
+            destination,
+            &user,
+            &[],
+            amount,
+            DECIMALS,
+        ).unwrap();
+
+        self.send_instructions(&[ix], Payer::User)
+    }
+
+    /// Sends any `UpdateConfig` instruction with `payer` signing as the
+    /// authority.
+    pub fn update_config(&mut self, data: impl InstructionData, payer: Payer) -> TransactionResult {
+        let accounts = accounts::UpdateConfig {
+            authority: self.signer(payer).pubkey(),
+            config: self.config,
+        };
+
+        self.send(accounts, data, payer)
+    }
+
+    fn signer(&self, payer: Payer) -> &Keypair {
+        match payer {
+            Payer::Authority => &self.authority,
+            Payer::User => &self.user,
+        }
+    }
+
+    fn send(&mut self, accounts: impl ToAccountMetas, data: impl InstructionData, payer: Payer) -> TransactionResult {
+        self.send_instructions(&[amm_instruction(accounts, data)], payer)
+    }
+
+    /// Sends `ixs` as a single transaction signed by `payer`.
+    pub fn send_instructions(&mut self, ixs: &[Instruction], payer: Payer) -> TransactionResult {
+        let signer = self.signer(payer);
+
+        let tx = Transaction::new_signed_with_payer(
+            ixs,
+            Some(&signer.pubkey()),
+            &[signer],
+            self.svm.latest_blockhash(),
+        );
+        let result = self.svm.send_transaction(tx);
+
+        // Identical instructions sent twice would otherwise be rejected as
+        // duplicate transactions.
+        self.svm.expire_blockhash();
+
+        result
+    }
+}
+
+impl Default for Fixture {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// One step of a random sequence of operations on the fixture's pool.
+#[derive(Clone, Copy, Debug)]
+pub enum Action {
+    Deposit { lp: u64 },
+    Swap { is_x: bool, amount: u64 },
+    /// Basis points of the user's LP balance.
+    Withdraw { share: u16 },
+}
+
+pub fn action() -> impl Strategy<Value = Action> {
+    prop_oneof![
+        (1u64..1 << 30).prop_map(|lp| Action::Deposit { lp }),
+        (any::<bool>(), 1u64..1 << 30).prop_map(|(is_x, amount)| Action::Swap { is_x, amount }),
+        (1u16..=10_000).prop_map(|share| Action::Withdraw { share }),
+    ]
+}
+
+/// Balances of the fixture's pool and user.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct State {
+    pub x: u64,
+    pub y: u64,
+    pub lp: u64,
+    pub user_x: u64,
+    pub user_y: u64,
+    pub user_lp: u64,
+}
+
+impl State {
+    pub fn read(fixture: &Fixture) -> Self {
+        Self {
+            x: fixture.vault_balance(&fixture.mint_x),
+            y: fixture.vault_balance(&fixture.mint_y),
+            lp: fixture.lp_supply(),
+            user_x: fixture.user_balance(&fixture.mint_x),
+            user_y: fixture.user_balance(&fixture.mint_y),
+            user_lp: fixture.user_balance(&fixture.mint_lp),
+        }
+    }
+
+    pub fn k(&self) -> u128 {
+        self.x as u128 * self.y as u128
+    }
+}
+
+/// Addresses of a pool created through a [`Fixture`].
+#[derive(Clone, Copy, Debug)]
+pub struct PoolKeys {
+    pub seed: u64,
+    pub config: Pubkey,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub mint_lp: Pubkey,
+}
+
+impl PoolKeys {
+    pub fn new(seed: u64, mint_x: Pubkey, mint_y: Pubkey) -> Self {
+        let config = Pubkey::find_program_address(&[b"config", &seed.to_le_bytes()], &amm::ID).0;
+        let mint_lp = Pubkey::find_program_address(&[b"lp", config.as_ref()], &amm::ID).0;
+
+        Self {
+            seed,
+            config,
+            mint_x,
+            mint_y,
+            mint_lp,
+        }
+    }
+}
+
+fn funded_mint(svm: &mut LiteSVM, authority: &Keypair, user: &Keypair, kind: MintKind) -> Pubkey {
+    let mint = Pubkey::new_unique();
+    let token_program = kind.token_program();
+
+    let extensions = match kind {
+        MintKind::TransferFee { .. } => vec![ExtensionType::TransferFeeConfig],
+        MintKind::Token | MintKind::Token2022 => Vec::new(),
+    };
+    let space = ExtensionType::try_calculate_account_len::<Mint>(&extensions).unwrap();
+    let account = Account {
+        lamports: svm.minimum_balance_for_rent_exemption(space),
+        data: vec![0; space],
+        owner: token_program,
+        executable: false,
+        rent_epoch: 0,
+    };
+    svm.set_account(mint, account).unwrap();
+
+    let mut ixs = Vec::new();
+    if let MintKind::TransferFee { bps } = kind {
+        ixs.push(initialize_transfer_fee_config(&token_program, &mint, None, None, bps, u64::MAX).unwrap());
+    }
+    ixs.push(initialize_mint2(&token_program, &mint, &authority.pubkey(), None, DECIMALS).unwrap());
+    ixs.push(spl_associated_token_account::instruction::create_associated_token_account(
+        &authority.pubkey(),
+        &user.pubkey(),
+        &mint,
+        &token_program,
+    ));
+    let ata = get_associated_token_address_with_program_id(&user.pubkey(), &mint, &token_program);
+    ixs.push(mint_to(&token_program, &mint, &ata, &authority.pubkey(), &[], USER_BALANCE).unwrap());
+
+    let tx = Transaction::new_signed_with_payer(&ixs, Some(&authority.pubkey()), &[authority], svm.latest_blockhash());
+    svm.send_transaction(tx).unwrap();
+
+    mint
+}
+
+/// An instruction for the amm program.
+pub fn amm_instruction(accounts: impl ToAccountMetas, data: impl InstructionData) -> Instruction {
+    Instruction {
+        program_id: amm::ID,
+        accounts: accounts.to_account_metas(None),
+        data: data.data(),
+    }
+}
+
+/// Which of the fixture's keypairs signs and pays for a transaction.
+#[derive(Clone, Copy, Debug)]
+pub enum Payer {
+    Authority,
+    User,
+}
+
+/// Asserts that `result` failed with `error` from the amm program.
+pub fn assert_amm_error(result: TransactionResult, error: AmmError) {
+    assert_amm_error_at(result, 0, error);
+}
+
+/// Asserts that `result` failed with `error` from its `index`th instruction.
+pub fn assert_amm_error_at(result: TransactionResult, index: u8, error: AmmError) {
+    let failed = result.expect_err("transaction should have failed");
+
+    assert_eq!(
+        failed.err,
+        TransactionError::InstructionError(index, InstructionError::Custom(error.into())),
+        "{:#?}",
+        failed.meta.logs,
+    );
+}
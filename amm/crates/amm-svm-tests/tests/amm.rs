@@ -0,0 +1,693 @@
+use amm::{error::AmmError, instruction, state::{spot_prices, Config, CurveType, DynamicFeeParams, PoolRegistry}, utils::allowlist_leaf};
+use amm_quote::{dynamic_fee::spot_price_change_bps, stable_swap};
+use amm_svm_tests::{assert_amm_error, assert_amm_error_at, Fixture, Payer, PoolKeys, USER_BALANCE};
+use anchor_lang::{prelude::Pubkey, solana_program::hash::hashv, AccountDeserialize};
+use solana_sdk::signer::Signer;
+
+const FEES: u16 = 30;
+const INITIAL_X: u64 = 1_000_000_000;
+const INITIAL_Y: u64 = 4_000_000_000;
+
+/// A fixture whose pool holds `INITIAL_X` and `INITIAL_Y`.
+fn funded_pool() -> Fixture {
+    let mut fixture = Fixture::new();
+    fixture.initialize(FEES).unwrap();
+    fixture.deposit(1, INITIAL_X, INITIAL_Y).unwrap();
+
+    fixture
+}
+
+#[test]
+fn initialize_creates_unlocked_pool() {
+    let mut fixture = Fixture::new();
+    fixture.initialize(FEES).unwrap();
+
+    let config = fixture.config_account();
+    assert_eq!(config.mint_x, fixture.mint_x);
+    assert_eq!(config.mint_y, fixture.mint_y);
+    assert_eq!(config.fees, FEES);
+    assert!(!config.locked);
+}
+
+#[test]
+fn initialize_rejects_fees_above_maximum() {
+    let mut fixture = Fixture::new();
+
+    assert_amm_error(fixture.initialize(Config::MAX_FEES + 1), AmmError::InvalidFee);
+}
+
+#[test]
+fn first_deposit_locks_minimum_liquidity() {
+    let fixture = funded_pool();
+
+    // sqrt(1e9 * 4e9) = 2e9
+    assert_eq!(fixture.user_balance(&fixture.mint_lp), 2_000_000_000 - Config::MINIMUM_LIQUIDITY);
+    assert_eq!(fixture.vault_balance(&fixture.mint_lp), Config::MINIMUM_LIQUIDITY);
+    assert_eq!(fixture.vault_balance(&fixture.mint_x), INITIAL_X);
+    assert_eq!(fixture.vault_balance(&fixture.mint_y), INITIAL_Y);
+}
+
+#[test]
+fn donation_before_first_deposit_does_not_brick_pool() {
+    let mut fixture = Fixture::new();
+    fixture.initialize(FEES).unwrap();
+    let (mint_x, vault_x) = (fixture.mint_x, fixture.ata(&fixture.config, &fixture.mint_x));
+
+    fixture.transfer(&mint_x, &vault_x, 1).unwrap();
+    fixture.deposit(1, INITIAL_X, INITIAL_Y).unwrap();
+
+    // The first deposit still mints against what it put in; the donation
+    // accrues to the pool.
+    assert_eq!(fixture.user_balance(&fixture.mint_lp), 2_000_000_000 - Config::MINIMUM_LIQUIDITY);
+    assert_eq!(fixture.vault_balance(&fixture.mint_x), INITIAL_X + 1);
+
+    fixture.deposit(1_000_000, u64::MAX, u64::MAX).unwrap();
+}
+
+#[test]
+fn deposit_is_proportional() {
+    let mut fixture = funded_pool();
+    let lp_before = fixture.user_balance(&fixture.mint_lp);
+
+    fixture.deposit(200_000_000, u64::MAX, u64::MAX).unwrap();
+
+    assert_eq!(fixture.user_balance(&fixture.mint_lp), lp_before + 200_000_000);
+    assert_eq!(fixture.vault_balance(&fixture.mint_x), INITIAL_X + 100_000_000);
+    assert_eq!(fixture.vault_balance(&fixture.mint_y), INITIAL_Y + 400_000_000);
+}
+
+#[test]
+fn deposit_single_mints_the_quoted_lp() {
+    let mut fixture = funded_pool();
+    let amount = 100_000_000;
+    let (mint_x, mint_y, mint_lp) = (fixture.mint_x, fixture.mint_y, fixture.mint_lp);
+    let (vault_x, vault_y) = (fixture.vault_balance(&mint_x), fixture.vault_balance(&mint_y));
+    let user_lp = fixture.user_balance(&mint_lp);
+
+    let pool = fixture.config_account().pool(vault_x, vault_y, fixture.lp_supply(), fixture.now()).unwrap();
+    let (_, lp) = pool.deposit_single(true, amount).unwrap();
+
+    assert_amm_error(fixture.deposit_single(true, amount, lp + 1), AmmError::SlippageExceeded);
+    fixture.deposit_single(true, amount, lp).unwrap();
+
+    // The swapped half never leaves the vault.
+    assert_eq!(fixture.user_balance(&mint_lp), user_lp + lp);
+    assert_eq!(fixture.vault_balance(&mint_x), vault_x + amount);
+    assert_eq!(fixture.vault_balance(&mint_y), vault_y);
+}
+
+#[test]
+fn deposit_single_needs_existing_liquidity() {
+    let mut fixture = Fixture::new();
+    fixture.initialize(FEES).unwrap();
+
+    assert_amm_error(fixture.deposit_single(true, 100_000_000, 1), AmmError::NoLiquidity);
+}
+
+#[test]
+fn deposit_fails_above_max_amounts() {
+    let mut fixture = funded_pool();
+
+    assert_amm_error(fixture.deposit(200_000_000, 99_999_999, u64::MAX), AmmError::SlippageExceeded);
+}
+
+#[test]
+fn swap_pays_constant_product_output() {
+    let mut fixture = funded_pool();
+    let amount = 10_000_000;
+
+    fixture.swap(true, amount, 1).unwrap();
+
+    let amount_in = amount * (10_000 - FEES as u64) / 10_000;
+    let expected = INITIAL_Y * amount_in / (INITIAL_X + amount_in);
+    assert_eq!(fixture.user_balance(&fixture.mint_x), USER_BALANCE - INITIAL_X - amount);
+    assert_eq!(fixture.user_balance(&fixture.mint_y), USER_BALANCE - INITIAL_Y + expected);
+    assert_eq!(fixture.vault_balance(&fixture.mint_x), INITIAL_X + amount);
+    assert_eq!(fixture.vault_balance(&fixture.mint_y), INITIAL_Y - expected);
+}
+
+#[test]
+fn swap_fails_below_min_out() {
+    let mut fixture = funded_pool();
+
+    assert_amm_error(fixture.swap(true, 10_000_000, 40_000_000), AmmError::SlippageExceeded);
+}
+
+#[test]
+fn swap_route_bounds_only_the_final_output() {
+    let mut fixture = funded_pool();
+    let first = fixture.pool();
+    let mint_z = fixture.create_mint();
+    let second = PoolKeys::new(7, first.mint_y, mint_z);
+    fixture.initialize_pool(&second, FEES, CurveType::ConstantProduct, 0).unwrap();
+    fixture.deposit_pool(&second, 1, INITIAL_Y, INITIAL_X, Vec::new()).unwrap();
+    let amount = 10_000_000;
+
+    let after_fee = |amount: u64| amount * (10_000 - FEES as u64) / 10_000;
+    let y_out = INITIAL_Y * after_fee(amount) / (INITIAL_X + after_fee(amount));
+    let z_out = INITIAL_X * after_fee(y_out) / (INITIAL_Y + after_fee(y_out));
+    let route = [(first, true), (second, true)];
+    let user_y = fixture.user_balance(&first.mint_y);
+
+    assert_amm_error(fixture.swap_route(&route, amount, z_out + 1), AmmError::SlippageExceeded);
+    fixture.swap_route(&route, amount, z_out).unwrap();
+
+    assert_eq!(fixture.user_balance(&mint_z), USER_BALANCE - INITIAL_X + z_out);
+    assert_eq!(fixture.user_balance(&first.mint_y), user_y);
+    assert_eq!(fixture.balance(&second.config, &first.mint_y), INITIAL_Y + y_out);
+}
+
+#[test]
+fn swap_route_rejects_hops_that_do_not_chain() {
+    let mut fixture = funded_pool();
+    let first = fixture.pool();
+    let mint_z = fixture.create_mint();
+    let second = PoolKeys::new(7, first.mint_y, mint_z);
+    fixture.initialize_pool(&second, FEES, CurveType::ConstantProduct, 0).unwrap();
+    fixture.deposit_pool(&second, 1, INITIAL_Y, INITIAL_X, Vec::new()).unwrap();
+
+    // The second hop starts from Z, but the first one paid out Y.
+    assert_amm_error(fixture.swap_route(&[(first, true), (second, false)], 10_000_000, 1), AmmError::InvalidRoute);
+}
+
+#[test]
+fn withdraw_returns_proportional_share() {
+    let mut fixture = funded_pool();
+    let lp = fixture.user_balance(&fixture.mint_lp);
+
+    fixture.withdraw(lp, 1, 1).unwrap();
+
+    assert_eq!(fixture.user_balance(&fixture.mint_lp), 0);
+    // Only the locked minimum liquidity's share stays behind.
+    assert_eq!(fixture.vault_balance(&fixture.mint_x), 500);
+    assert_eq!(fixture.vault_balance(&fixture.mint_y), 2_000);
+}
+
+#[test]
+fn withdraw_fails_below_min_amounts() {
+    let mut fixture = funded_pool();
+
+    assert_amm_error(fixture.withdraw(1_000_000, 500_001, 1), AmmError::SlippageExceeded);
+}
+
+#[test]
+fn locked_pool_rejects_liquidity_and_swaps_until_unlocked() {
+    let mut fixture = funded_pool();
+    fixture.lock().unwrap();
+
+    assert_amm_error(fixture.deposit(1_000_000, u64::MAX, u64::MAX), AmmError::PoolLocked);
+    assert_amm_error(fixture.swap(true, 1_000_000, 1), AmmError::PoolLocked);
+    assert_amm_error(fixture.withdraw(1_000_000, 1, 1), AmmError::PoolLocked);
+
+    fixture.unlock().unwrap();
+    fixture.swap(true, 1_000_000, 1).unwrap();
+}
+
+#[test]
+fn config_updates_require_the_authority() {
+    let mut fixture = funded_pool();
+
+    assert_amm_error(fixture.update_config(instruction::Lock {}, Payer::User), AmmError::Unauthorized);
+    assert_amm_error(fixture.update_config(instruction::UpdateFees { fees: 100 }, Payer::User), AmmError::Unauthorized);
+
+    let config = fixture.config_account();
+    assert!(!config.locked);
+    assert_eq!(config.fees, FEES);
+}
+
+#[test]
+fn renounced_pool_is_immutable() {
+    let mut fixture = funded_pool();
+
+    fixture.update_config(instruction::RenounceAuthority {}, Payer::Authority).unwrap();
+    assert_eq!(fixture.config_account().authority, None);
+
+    assert_amm_error(fixture.lock(), AmmError::Unauthorized);
+    assert_amm_error(fixture.update_config(instruction::UpdateFees { fees: 100 }, Payer::Authority), AmmError::Unauthorized);
+    assert_amm_error(
+        fixture.update_config(instruction::TransferAuthority { new_authority: fixture.user.pubkey() }, Payer::Authority),
+        AmmError::Unauthorized,
+    );
+
+    // Swaps and liquidity keep working.
+    fixture.swap(true, 1_000_000, 1).unwrap();
+}
+
+#[test]
+fn protocol_fees_are_kept_out_of_reserves_until_collected() {
+    let mut fixture = funded_pool();
+    fixture.update_config(instruction::UpdateProtocolFee { protocol_fee: 2_000 }, Payer::Authority).unwrap();
+    let amount = 10_000_000;
+    let amount_in = amount * (10_000 - FEES as u64) / 10_000;
+
+    fixture.swap(true, amount, 1).unwrap();
+
+    let protocol_fee_x = (amount - amount_in) * 2_000 / 10_000;
+    let config = fixture.config_account();
+    assert_eq!(config.protocol_fees_x, protocol_fee_x);
+    assert_eq!(config.protocol_fees_y, 0);
+
+    // The next swap prices against the vaults minus what the protocol is owed.
+    let reserve_x = fixture.vault_balance(&fixture.mint_x) - protocol_fee_x;
+    let reserve_y = fixture.vault_balance(&fixture.mint_y);
+    let user_x = fixture.user_balance(&fixture.mint_x);
+
+    fixture.swap(false, amount, 1).unwrap();
+
+    let expected = reserve_x * amount_in / (reserve_y + amount_in);
+    assert_eq!(fixture.user_balance(&fixture.mint_x), user_x + expected);
+
+    let config = fixture.config_account();
+    let (fees_x, fees_y) = (config.protocol_fees_x, config.protocol_fees_y);
+    assert_eq!(fees_y, (amount - amount_in) * 2_000 / 10_000);
+    let (vault_x, vault_y) = (fixture.vault_balance(&fixture.mint_x), fixture.vault_balance(&fixture.mint_y));
+    let treasury = Pubkey::new_unique();
+
+    fixture.collect_protocol_fees(&treasury).unwrap();
+
+    assert_eq!(fixture.balance(&treasury, &fixture.mint_x), fees_x);
+    assert_eq!(fixture.balance(&treasury, &fixture.mint_y), fees_y);
+    assert_eq!(fixture.vault_balance(&fixture.mint_x), vault_x - fees_x);
+    assert_eq!(fixture.vault_balance(&fixture.mint_y), vault_y - fees_y);
+    let config = fixture.config_account();
+    assert_eq!((config.protocol_fees_x, config.protocol_fees_y), (0, 0));
+
+    assert_amm_error(fixture.collect_protocol_fees(&treasury), AmmError::ZeroAmount);
+}
+
+#[test]
+fn renouncing_hands_the_protocol_fee_to_lps() {
+    let mut fixture = funded_pool();
+    fixture.update_config(instruction::UpdateProtocolFee { protocol_fee: 2_000 }, Payer::Authority).unwrap();
+    fixture.swap(true, 10_000_000, 1).unwrap();
+
+    assert_amm_error(
+        fixture.update_config(instruction::RenounceAuthority {}, Payer::Authority),
+        AmmError::ProtocolFeesUncollected,
+    );
+
+    fixture.collect_protocol_fees(&Pubkey::new_unique()).unwrap();
+    fixture.update_config(instruction::RenounceAuthority {}, Payer::Authority).unwrap();
+    assert_eq!(fixture.config_account().protocol_fee, 0);
+
+    // The whole fee now stays in the reserves, nothing is set aside.
+    let mint_x = fixture.mint_x;
+    let vault_x = fixture.vault_balance(&mint_x);
+    fixture.swap(true, 10_000_000, 1).unwrap();
+
+    let config = fixture.config_account();
+    assert_eq!((config.protocol_fees_x, config.protocol_fees_y), (0, 0));
+    assert_eq!(fixture.vault_balance(&mint_x), vault_x + 10_000_000);
+}
+
+#[test]
+fn twap_weights_spot_prices_by_time() {
+    let mut fixture = funded_pool();
+    let start = fixture.config_account().observe(INITIAL_X, INITIAL_Y, fixture.now()).unwrap();
+    assert_eq!(start.twap_x(&start), None);
+
+    fixture.warp(100);
+    fixture.swap(true, 100_000_000, 1).unwrap();
+
+    let (x, y) = (fixture.vault_balance(&fixture.mint_x), fixture.vault_balance(&fixture.mint_y));
+    let config = fixture.config_account();
+    let middle = config.observe(x, y, fixture.now()).unwrap();
+
+    // The swap accumulated the pre-trade price up to its own timestamp.
+    assert_eq!(middle.price_x_cumulative, config.price_x_cumulative);
+    assert_eq!(start.twap_x(&middle), Some(spot_prices(INITIAL_X, INITIAL_Y).0));
+    assert_eq!(start.twap_y(&middle), Some(spot_prices(INITIAL_X, INITIAL_Y).1));
+
+    fixture.warp(300);
+    let end = config.observe(x, y, fixture.now()).unwrap();
+
+    let (before_x, before_y) = spot_prices(INITIAL_X, INITIAL_Y);
+    let (after_x, after_y) = spot_prices(x, y);
+    assert_eq!(start.twap_x(&end), Some((before_x * 100 + after_x * 300) / 400));
+    assert_eq!(start.twap_y(&end), Some((before_y * 100 + after_y * 300) / 400));
+    assert_eq!(middle.twap_x(&end), Some(after_x));
+}
+
+#[test]
+fn flash_loan_fee_stays_in_the_vault() {
+    let mut fixture = funded_pool();
+    let (pool, mint_x) = (fixture.pool(), fixture.mint_x);
+    let amount = 100_000_000;
+    let fee = (amount * FEES as u64).div_ceil(10_000);
+    let user_x = fixture.user_balance(&mint_x);
+
+    let ixs = [fixture.flash_borrow_ix(&pool, &mint_x, amount), fixture.flash_repay_ix(&pool, &mint_x)];
+    fixture.send_instructions(&ixs, Payer::User).unwrap();
+
+    assert_eq!(fixture.vault_balance(&mint_x), INITIAL_X + fee);
+    assert_eq!(fixture.user_balance(&mint_x), user_x - fee);
+    assert_eq!(fixture.config_account().flash_loan_owed, 0);
+}
+
+#[test]
+fn flash_borrow_requires_a_repay_for_the_same_pool() {
+    let mut fixture = funded_pool();
+    let (pool, mint_x) = (fixture.pool(), fixture.mint_x);
+    let other = PoolKeys::new(7, fixture.mint_x, fixture.mint_y);
+    fixture.initialize_pool(&other, FEES, CurveType::ConstantProduct, 0).unwrap();
+    fixture.deposit_pool(&other, 1, INITIAL_X, INITIAL_Y, Vec::new()).unwrap();
+
+    let borrow = fixture.flash_borrow_ix(&pool, &mint_x, 1_000_000);
+    assert_amm_error(fixture.send_instructions(&[borrow.clone()], Payer::User), AmmError::MissingFlashRepay);
+
+    let repay = fixture.flash_repay_ix(&other, &mint_x);
+    assert_amm_error(fixture.send_instructions(&[borrow, repay], Payer::User), AmmError::MissingFlashRepay);
+}
+
+#[test]
+fn flash_repay_must_use_the_borrowed_mint() {
+    let mut fixture = funded_pool();
+    let (pool, mint_x, mint_y) = (fixture.pool(), fixture.mint_x, fixture.mint_y);
+
+    let ixs = [fixture.flash_borrow_ix(&pool, &mint_x, 1_000_000), fixture.flash_repay_ix(&pool, &mint_y)];
+    assert_amm_error_at(fixture.send_instructions(&ixs, Payer::User), 1, AmmError::InvalidVault);
+}
+
+#[test]
+fn pool_is_frozen_while_a_flash_loan_is_open() {
+    let mut fixture = funded_pool();
+    let (pool, mint_x) = (fixture.pool(), fixture.mint_x);
+
+    for ix in [
+        fixture.swap_ix(false, 1_000_000, 1),
+        fixture.deposit_ix(&pool, 1_000, INITIAL_X, INITIAL_Y, Vec::new()),
+        fixture.withdraw_ix(1_000, 0, 0),
+    ] {
+        let ixs = [fixture.flash_borrow_ix(&pool, &mint_x, 1_000_000), ix, fixture.flash_repay_ix(&pool, &mint_x)];
+        assert_amm_error_at(fixture.send_instructions(&ixs, Payer::User), 1, AmmError::FlashLoanActive);
+    }
+}
+
+#[test]
+fn ramp_amp_is_only_for_stable_swap_pools() {
+    let mut fixture = funded_pool();
+    let ramp_end = fixture.now() + Config::MIN_AMP_RAMP_DURATION;
+
+    assert_amm_error(
+        fixture.update_config(instruction::RampAmp { amp_target: 200, ramp_end }, Payer::Authority),
+        AmmError::NotStableSwap,
+    );
+}
+
+#[test]
+fn ramp_amp_is_bounded_and_interpolated() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.pool();
+    fixture.initialize_pool(&pool, FEES, CurveType::StableSwap, 100).unwrap();
+    let start = fixture.now();
+    let ramp_end = start + Config::MIN_AMP_RAMP_DURATION;
+
+    for (amp_target, ramp_end) in [(200, ramp_end - 1), (100 * Config::MAX_AMP_CHANGE + 1, ramp_end), (9, ramp_end)] {
+        assert_amm_error(
+            fixture.update_config(instruction::RampAmp { amp_target, ramp_end }, Payer::Authority),
+            AmmError::InvalidAmpRamp,
+        );
+    }
+
+    fixture.update_config(instruction::RampAmp { amp_target: 300, ramp_end }, Payer::Authority).unwrap();
+
+    // A second ramp can't start until the first one ends.
+    assert_amm_error(
+        fixture.update_config(instruction::RampAmp { amp_target: 200, ramp_end: ramp_end + 1 }, Payer::Authority),
+        AmmError::InvalidAmpRamp,
+    );
+
+    fixture.warp(Config::MIN_AMP_RAMP_DURATION / 2);
+    assert_eq!(fixture.config_account().amp(fixture.now()), 200);
+    fixture.warp(Config::MIN_AMP_RAMP_DURATION);
+    assert_eq!(fixture.config_account().amp(fixture.now()), 300);
+}
+
+#[test]
+fn stable_swap_oracle_and_volatility_use_the_marginal_price() {
+    let mut fixture = Fixture::new();
+    let pool = fixture.pool();
+    fixture.initialize_pool(&pool, FEES, CurveType::StableSwap, 100).unwrap();
+    fixture.deposit(1, INITIAL_X, INITIAL_Y).unwrap();
+    fixture
+        .set_dynamic_fee(DynamicFeeParams { min_fees: 10, max_fees: 100, max_volatility: 1_000_000, decay_period: 3_600 })
+        .unwrap();
+    let start = fixture.config_account().observe(INITIAL_X, INITIAL_Y, fixture.now()).unwrap();
+
+    fixture.warp(100);
+    fixture.swap(true, 100_000_000, 1).unwrap();
+
+    let config = fixture.config_account();
+    let (x, y) = config
+        .reserves(fixture.vault_balance(&fixture.mint_x), fixture.vault_balance(&fixture.mint_y))
+        .unwrap();
+    let middle = config.observe(x, y, fixture.now()).unwrap();
+
+    // At 1:4 the stable curve prices X well below the reserve ratio.
+    let (price_x, price_y) = stable_swap::spot_prices(100, INITIAL_X, INITIAL_Y).unwrap();
+    assert!(price_x < spot_prices(INITIAL_X, INITIAL_Y).0 / 2);
+    assert_eq!(start.twap_x(&middle), Some(price_x));
+    assert_eq!(start.twap_y(&middle), Some(price_y));
+
+    let after_x = stable_swap::spot_prices(100, x, y).unwrap().0;
+    assert_eq!(config.volatility, spot_price_change_bps(price_x, after_x));
+}
+
+#[test]
+fn only_immutable_open_pools_can_be_registered() {
+    let mut fixture = funded_pool();
+
+    assert_amm_error(fixture.register_pool().1, AmmError::PoolNotRegistrable);
+
+    fixture.set_deposit_caps(INITIAL_X * 2, INITIAL_Y * 2).unwrap();
+    fixture.update_config(instruction::RenounceAuthority {}, Payer::Authority).unwrap();
+    assert_amm_error(fixture.register_pool().1, AmmError::PoolNotRegistrable);
+}
+
+#[test]
+fn renounced_pool_can_be_registered_once() {
+    let mut fixture = funded_pool();
+    fixture.update_config(instruction::RenounceAuthority {}, Payer::Authority).unwrap();
+
+    let (registry, result) = fixture.register_pool();
+    result.unwrap();
+
+    let account = fixture.svm.get_account(&registry).unwrap();
+    let entry = PoolRegistry::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(entry.config, fixture.config);
+    assert_eq!(entry.fees, FEES);
+    assert_eq!(entry.curve, CurveType::ConstantProduct);
+    assert!(fixture.register_pool().1.is_err());
+}
+
+#[test]
+fn initialize_can_claim_the_registry_per_curve() {
+    let mut fixture = Fixture::new();
+    let (mint_x, mint_y) = (fixture.mint_x, fixture.mint_y);
+
+    let (registry, result) = fixture.initialize_registered(&fixture.pool(), FEES, CurveType::ConstantProduct, 0);
+    result.unwrap();
+
+    let account = fixture.svm.get_account(&registry).unwrap();
+    let entry = PoolRegistry::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(entry.config, fixture.config);
+    assert_eq!(entry.curve, CurveType::ConstantProduct);
+
+    // The entry is taken for this pair, fee tier and curve, in either order.
+    let duplicate = PoolKeys::new(43, mint_y, mint_x);
+    assert!(fixture.initialize_registered(&duplicate, FEES, CurveType::ConstantProduct, 0).1.is_err());
+
+    // A StableSwap pool on the same pair and fee tier has its own entry.
+    let stable = PoolKeys::new(44, mint_x, mint_y);
+    let (stable_registry, result) = fixture.initialize_registered(&stable, FEES, CurveType::StableSwap, 100);
+    result.unwrap();
+    assert_ne!(stable_registry, registry);
+
+    let account = fixture.svm.get_account(&stable_registry).unwrap();
+    let entry = PoolRegistry::try_deserialize(&mut account.data.as_slice()).unwrap();
+    assert_eq!(entry.config, stable.config);
+    assert_eq!(entry.curve, CurveType::StableSwap);
+}
+
+#[test]
+fn concentrated_positions_respect_the_minimum_liquidity() {
+    let mut fixture = Fixture::new();
+    let min = 1_000_000;
+
+    assert_amm_error(fixture.initialize_concentrated(1, 60, 0).1, AmmError::ZeroAmount);
+    let (pool, result) = fixture.initialize_concentrated(1, 60, min);
+    result.unwrap();
+    let (position, result) = fixture.open_position(&pool, -600, 600);
+    result.unwrap();
+
+    // Dust positions can't fill the pool's inline tick storage.
+    assert_amm_error(fixture.increase_liquidity(&pool, &position, min - 1), AmmError::PositionTooSmall);
+    fixture.increase_liquidity(&pool, &position, min).unwrap();
+
+    // Withdrawing may empty a position but not leave dust behind.
+    assert_amm_error(fixture.decrease_liquidity(&pool, &position, 1), AmmError::PositionTooSmall);
+    fixture.decrease_liquidity(&pool, &position, min).unwrap();
+}
+
+const WIDE_LIQUIDITY: u128 = 10_000_000_000;
+const NARROW_LIQUIDITY: u128 = 30_000_000_000;
+
+/// A concentrated pool at a price of one with the user's wide position over
+/// `[-1200, 1200)` and narrow position over `[-120, 120)`. Returns the pool,
+/// wide and narrow position addresses.
+fn overlapping_positions() -> (Fixture, Pubkey, Pubkey, Pubkey) {
+    let mut fixture = Fixture::new();
+    let (pool, result) = fixture.initialize_concentrated(1, 60, 1_000);
+    result.unwrap();
+
+    let (wide, result) = fixture.open_position(&pool, -1200, 1200);
+    result.unwrap();
+    fixture.increase_liquidity(&pool, &wide, WIDE_LIQUIDITY).unwrap();
+    let (narrow, result) = fixture.open_position(&pool, -120, 120);
+    result.unwrap();
+    fixture.increase_liquidity(&pool, &narrow, NARROW_LIQUIDITY).unwrap();
+
+    (fixture, pool, wide, narrow)
+}
+
+#[test]
+fn concentrated_swaps_cross_initialized_ticks() {
+    let (mut fixture, pool, _, _) = overlapping_positions();
+    let mint_y = fixture.mint_y;
+    assert_eq!(fixture.concentrated_pool(&pool).liquidity, WIDE_LIQUIDITY + NARROW_LIQUIDITY);
+
+    // Selling X pushes the price below the narrow range, leaving only the
+    // wide position's liquidity active.
+    let user_y = fixture.user_balance(&mint_y);
+    fixture.swap_concentrated(&pool, true, 300_000_000, 1).unwrap();
+
+    let state = fixture.concentrated_pool(&pool);
+    assert!(-1200 <= state.tick && state.tick < -120);
+    assert_eq!(state.liquidity, WIDE_LIQUIDITY);
+    assert_eq!(fixture.user_balance(&mint_y), user_y + 296_630_738);
+
+    // Buying it back crosses both narrow boundaries on the way up.
+    fixture.swap_concentrated(&pool, false, 1_000_000_000, 1).unwrap();
+
+    let state = fixture.concentrated_pool(&pool);
+    assert!(120 <= state.tick && state.tick < 1200);
+    assert_eq!(state.liquidity, WIDE_LIQUIDITY);
+}
+
+#[test]
+fn overlapping_positions_earn_fees_by_in_range_liquidity() {
+    let (mut fixture, pool, wide, narrow) = overlapping_positions();
+    let mint_x = fixture.mint_x;
+
+    // Both positions are in range; the 29_999 fee splits 1:3, rounded down.
+    fixture.swap_concentrated(&pool, true, 10_000_000, 1).unwrap();
+
+    let user_x = fixture.user_balance(&mint_x);
+    fixture.collect_fees(&pool, &wide).unwrap();
+    assert_eq!(fixture.user_balance(&mint_x), user_x + 7_499);
+    fixture.collect_fees(&pool, &narrow).unwrap();
+    assert_eq!(fixture.user_balance(&mint_x), user_x + 7_499 + 22_499);
+
+    // Once the price has left the narrow range, only the wide position earns.
+    fixture.swap_concentrated(&pool, true, 300_000_000, 1).unwrap();
+    fixture.collect_fees(&pool, &narrow).unwrap();
+    fixture.swap_concentrated(&pool, true, 10_000_000, 1).unwrap();
+
+    let user_x = fixture.user_balance(&mint_x);
+    fixture.collect_fees(&pool, &narrow).unwrap();
+    assert_eq!(fixture.user_balance(&mint_x), user_x);
+    fixture.collect_fees(&pool, &wide).unwrap();
+    assert!(fixture.user_balance(&mint_x) > user_x);
+}
+
+#[test]
+fn close_position_requires_withdrawn_liquidity_and_collected_fees() {
+    let (mut fixture, pool, wide, narrow) = overlapping_positions();
+    let user = fixture.user.pubkey();
+    fixture.swap_concentrated(&pool, true, 10_000_000, 1).unwrap();
+
+    assert_amm_error(fixture.close_position(&pool, &narrow), AmmError::PositionNotEmpty);
+
+    // Withdrawing frees the narrow range's ticks but leaves its fees owed.
+    fixture.decrease_liquidity(&pool, &narrow, NARROW_LIQUIDITY).unwrap();
+    let indices: Vec<i32> = fixture.concentrated_pool(&pool).ticks.iter().map(|tick| tick.index).collect();
+    assert_eq!(indices, [-1200, 1200]);
+    assert_amm_error(fixture.close_position(&pool, &narrow), AmmError::PositionNotEmpty);
+
+    fixture.collect_fees(&pool, &narrow).unwrap();
+    let lamports = fixture.svm.get_balance(&user).unwrap();
+    fixture.close_position(&pool, &narrow).unwrap();
+
+    // The rent comes back to the owner, net of the transaction fee.
+    assert!(fixture.svm.get_balance(&user).unwrap() > lamports);
+    assert!(fixture.svm.get_account(&narrow).map_or(true, |account| account.lamports == 0));
+    assert_eq!(fixture.concentrated_pool(&pool).liquidity, WIDE_LIQUIDITY);
+    assert!(fixture.close_position(&pool, &wide).is_err());
+}
+
+#[test]
+fn zero_amounts_are_rejected() {
+    let mut fixture = funded_pool();
+
+    assert_amm_error(fixture.deposit(0, u64::MAX, u64::MAX), AmmError::ZeroAmount);
+    assert_amm_error(fixture.swap(true, 0, 0), AmmError::ZeroAmount);
+    assert_amm_error(fixture.withdraw(0, 0, 0), AmmError::ZeroAmount);
+}
+
+#[test]
+fn deposits_fail_above_reserve_cap() {
+    let mut fixture = funded_pool();
+    fixture.set_deposit_caps(INITIAL_X + 100_000_000, Config::UNCAPPED).unwrap();
+
+    fixture.deposit(200_000_000, u64::MAX, u64::MAX).unwrap();
+    assert_amm_error(fixture.deposit(2, u64::MAX, u64::MAX), AmmError::DepositCapExceeded);
+
+    // Swaps and withdrawals are never capped.
+    fixture.swap(true, 1_000_000, 1).unwrap();
+    fixture.withdraw(1_000_000, 1, 1).unwrap();
+}
+
+#[test]
+fn allowlisted_pool_only_accepts_listed_wallets() {
+    let mut fixture = funded_pool();
+    let other = allowlist_leaf(&Pubkey::new_unique());
+    let user = allowlist_leaf(&fixture.user.pubkey());
+    let (left, right) = match user <= other {
+        true => (user, other),
+        false => (other, user),
+    };
+    fixture.set_lp_allowlist(hashv(&[&[1], &left, &right]).to_bytes()).unwrap();
+
+    assert_amm_error(fixture.deposit(1_000_000, u64::MAX, u64::MAX), AmmError::NotAllowlisted);
+    assert_amm_error(
+        fixture.deposit_with_proof(1_000_000, u64::MAX, u64::MAX, vec![user]),
+        AmmError::NotAllowlisted,
+    );
+    fixture.deposit_with_proof(1_000_000, u64::MAX, u64::MAX, vec![other]).unwrap();
+
+    fixture.remove_lp_allowlist().unwrap();
+    fixture.deposit(1_000_000, u64::MAX, u64::MAX).unwrap();
+}
+
+#[test]
+fn dynamic_fee_rises_after_large_swaps() {
+    let mut fixture = funded_pool();
+    let params = DynamicFeeParams { min_fees: 10, max_fees: 100, max_volatility: 1_000, decay_period: 3_600 };
+
+    assert_amm_error(
+        fixture.set_dynamic_fee(DynamicFeeParams { min_fees: 200, ..params }),
+        AmmError::InvalidFee,
+    );
+    fixture.set_dynamic_fee(params).unwrap();
+    assert_eq!(fixture.config_account().current_fees(0), params.min_fees);
+
+    // Moves the price by about 20%, well past `max_volatility`.
+    fixture.swap(true, 100_000_000, 1).unwrap();
+
+    let config = fixture.config_account();
+    assert!(config.volatility >= params.max_volatility);
+    assert_eq!(config.current_fees(config.volatility_updated_at), params.max_fees);
+
+    fixture.disable_dynamic_fee().unwrap();
+    assert_eq!(fixture.config_account().current_fees(config.volatility_updated_at), FEES);
+}
@@ -0,0 +1,69 @@
+//! Random sequences of deposits, swaps and withdrawals against the deployed
+//! program, checking the pool invariants from on-chain balances after every
+//! transaction. Failed transactions must leave the pool untouched.
+
+use amm::state::Config;
+use amm_svm_tests::{action, Action, Fixture, State};
+use proptest::prelude::*;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn pool_invariants_hold_across_sequences(
+        initial_x in 1_000_000u64..1 << 32,
+        initial_y in 1_000_000u64..1 << 32,
+        fees in 0u16..1_000,
+        actions in prop::collection::vec(action(), 1..20),
+    ) {
+        let mut fixture = Fixture::new();
+        fixture.initialize(fees).unwrap();
+        fixture.deposit(1, initial_x, initial_y).unwrap();
+
+        for action in actions {
+            let before = State::read(&fixture);
+
+            let result = match action {
+                Action::Deposit { lp } => fixture.deposit(lp, u64::MAX, u64::MAX),
+                Action::Swap { is_x, amount } => fixture.swap(is_x, amount, 1),
+                Action::Withdraw { share } => {
+                    let lp = (before.user_lp as u128 * share as u128 / 10_000) as u64;
+                    fixture.withdraw(lp, 0, 0)
+                }
+            };
+
+            let after = State::read(&fixture);
+            if result.is_err() {
+                prop_assert_eq!(after, before);
+                continue;
+            }
+
+            // Tokens only move between the user and the vaults.
+            prop_assert_eq!(before.x + before.user_x, after.x + after.user_x);
+            prop_assert_eq!(before.y + before.user_y, after.y + after.user_y);
+
+            match action {
+                // k never decreases once fees are taken.
+                Action::Swap { .. } => {
+                    prop_assert_eq!(after.lp, before.lp);
+                    prop_assert!(after.k() >= before.k());
+                }
+                // LP supply moves in proportion to the reserves, with any
+                // rounding left in the pool.
+                Action::Deposit { .. } | Action::Withdraw { .. } => {
+                    prop_assert!(after.x as u128 * before.lp as u128 >= before.x as u128 * after.lp as u128);
+                    prop_assert!(after.y as u128 * before.lp as u128 >= before.y as u128 * after.lp as u128);
+                }
+            }
+        }
+
+        // The user can always exit; only the locked liquidity's share stays.
+        let state = State::read(&fixture);
+        if state.user_lp != 0 {
+            fixture.withdraw(state.user_lp, 0, 0).unwrap();
+        }
+        prop_assert_eq!(fixture.lp_supply(), Config::MINIMUM_LIQUIDITY);
+        prop_assert!(fixture.vault_balance(&fixture.mint_x) != 0);
+        prop_assert!(fixture.vault_balance(&fixture.mint_y) != 0);
+    }
+}
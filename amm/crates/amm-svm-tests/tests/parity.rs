@@ -0,0 +1,101 @@
+//! Quotes from `Config::pool`, built from the deserialized on-chain account,
+//! must match what the program actually moves, on either curve and with or
+//! without a protocol fee. Off-chain integrators rely on this to price
+//! transactions before sending them.
+
+use amm::{instruction, state::{Config, CurveType}};
+use amm_svm_tests::{action, Action, Fixture, Payer, State};
+use proptest::prelude::*;
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn quotes_match_on_chain_balance_changes(
+        initial_x in 1_000_000u64..1 << 32,
+        initial_y in 1_000_000u64..1 << 32,
+        fees in 0u16..1_000,
+        protocol_fee in prop_oneof![Just(0), 1u16..=Config::MAX_FEES],
+        amp in prop::option::of(1u64..10_000),
+        actions in prop::collection::vec(action(), 1..20),
+    ) {
+        let mut fixture = Fixture::new();
+        let curve = amp.map_or(CurveType::ConstantProduct, |_| CurveType::StableSwap);
+        fixture.initialize_pool(&fixture.pool(), fees, curve, amp.unwrap_or(0)).unwrap();
+        fixture.update_config(instruction::UpdateProtocolFee { protocol_fee }, Payer::Authority).unwrap();
+        fixture.deposit(1, initial_x, initial_y).unwrap();
+
+        for action in actions {
+            let before = State::read(&fixture);
+            let config = fixture.config_account();
+            let pool = config.pool(before.x, before.y, before.lp, fixture.now()).unwrap();
+
+            match action {
+                Action::Swap { is_x, amount } => {
+                    let quote = pool.swap(is_x, amount);
+                    let result = fixture.swap(is_x, amount, 1);
+                    let after = State::read(&fixture);
+
+                    match result {
+                        Ok(_) => {
+                            let quote = quote.expect("program swapped but the quote failed");
+                            let (vault_in, vault_out, user_in, user_out) = match is_x {
+                                true => (after.x - before.x, before.y - after.y, before.user_x - after.user_x, after.user_y - before.user_y),
+                                false => (after.y - before.y, before.x - after.x, before.user_y - after.user_y, after.user_x - before.user_x),
+                            };
+                            prop_assert_eq!(user_in, amount);
+                            prop_assert_eq!(vault_in, quote.deposit);
+                            prop_assert_eq!(vault_out, quote.withdraw);
+                            prop_assert_eq!(user_out, quote.withdraw);
+                            prop_assert_eq!(after.lp, before.lp);
+
+                            // The protocol's cut stays in the vault but is
+                            // owed to the treasury.
+                            let after_config = fixture.config_account();
+                            let accrued = match is_x {
+                                true => after_config.protocol_fees_x - config.protocol_fees_x,
+                                false => after_config.protocol_fees_y - config.protocol_fees_y,
+                            };
+                            prop_assert_eq!(accrued, quote.protocol_fee);
+                        }
+                        Err(_) => prop_assert_eq!(after, before),
+                    }
+                }
+                Action::Deposit { lp } => {
+                    let quote = pool.deposit(lp);
+                    let result = fixture.deposit(lp, u64::MAX, u64::MAX);
+                    let after = State::read(&fixture);
+
+                    match result {
+                        Ok(_) => {
+                            let quote = quote.expect("program deposited but the quote failed");
+                            prop_assert_eq!(after.x - before.x, quote.x);
+                            prop_assert_eq!(after.y - before.y, quote.y);
+                            prop_assert_eq!(after.lp - before.lp, quote.lp);
+                            prop_assert_eq!(after.user_lp - before.user_lp, quote.lp);
+                        }
+                        Err(_) => prop_assert_eq!(after, before),
+                    }
+                }
+                Action::Withdraw { share } => {
+                    let lp = (before.user_lp as u128 * share as u128 / 10_000) as u64;
+                    let quote = pool.withdraw(lp);
+                    let result = fixture.withdraw(lp, 0, 0);
+                    let after = State::read(&fixture);
+
+                    match result {
+                        Ok(_) => {
+                            let quote = quote.expect("program withdrew but the quote failed");
+                            prop_assert_eq!(before.x - after.x, quote.x);
+                            prop_assert_eq!(before.y - after.y, quote.y);
+                            prop_assert_eq!(before.lp - after.lp, quote.lp);
+                            prop_assert_eq!(after.user_x - before.user_x, quote.x);
+                            prop_assert_eq!(after.user_y - before.user_y, quote.y);
+                        }
+                        Err(_) => prop_assert_eq!(after, before),
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,144 @@
+//! Pools whose X mint is a Token-2022 mint with a transfer fee. The curve
+//! must price what the vaults actually receive, and slippage bounds apply to
+//! what the user actually receives.
+
+use amm::error::AmmError;
+use amm_quote::Pool;
+use amm_svm_tests::{assert_amm_error, Fixture, MintKind, Payer, USER_BALANCE};
+use anchor_spl::token_2022::spl_token_2022::extension::transfer_fee::TransferFee;
+
+const FEES: u16 = 30;
+const TRANSFER_FEE_BPS: u16 = 100;
+const INITIAL_X: u64 = 1_000_000_000;
+const INITIAL_Y: u64 = 4_000_000_000;
+
+/// The transfer fee configured on mint X.
+fn transfer_fee() -> TransferFee {
+    TransferFee {
+        epoch: 0u64.into(),
+        maximum_fee: u64::MAX.into(),
+        transfer_fee_basis_points: TRANSFER_FEE_BPS.into(),
+    }
+}
+
+fn funded_pool() -> Fixture {
+    let mut fixture = Fixture::with_mints(MintKind::TransferFee { bps: TRANSFER_FEE_BPS }, MintKind::Token);
+    fixture.initialize(FEES).unwrap();
+    fixture.deposit(1, INITIAL_X, INITIAL_Y).unwrap();
+
+    fixture
+}
+
+/// Off-chain quote for the fixture's pool as it stands.
+fn quote_pool(fixture: &Fixture) -> Pool {
+    let (vault_x, vault_y) = (fixture.vault_balance(&fixture.mint_x), fixture.vault_balance(&fixture.mint_y));
+
+    fixture.config_account().pool(vault_x, vault_y, fixture.lp_supply(), fixture.now()).unwrap()
+}
+
+#[test]
+fn first_deposit_counts_only_what_reaches_the_vault() {
+    let fixture = funded_pool();
+    let fee = transfer_fee().calculate_fee(INITIAL_X).unwrap();
+
+    assert_eq!(fixture.user_balance(&fixture.mint_x), USER_BALANCE - INITIAL_X);
+    assert_eq!(fixture.vault_balance(&fixture.mint_x), INITIAL_X - fee);
+    assert_eq!(fixture.vault_balance(&fixture.mint_y), INITIAL_Y);
+
+    let lp = Pool::initial_deposit(INITIAL_X - fee, INITIAL_Y).unwrap().lp;
+    assert_eq!(fixture.user_balance(&fixture.mint_lp), lp);
+}
+
+#[test]
+fn deposit_charges_the_transfer_fee_on_top() {
+    let mut fixture = funded_pool();
+    let (mint_x, mint_y, mint_lp) = (fixture.mint_x, fixture.mint_y, fixture.mint_lp);
+    let lp = 1_000_000;
+
+    let quote = quote_pool(&fixture).deposit(lp).unwrap();
+    let gross_x = quote.x + transfer_fee().calculate_inverse_fee(quote.x).unwrap();
+    let (user_x, vault_x, user_lp) = (fixture.user_balance(&mint_x), fixture.vault_balance(&mint_x), fixture.user_balance(&mint_lp));
+
+    assert_amm_error(fixture.deposit(lp, gross_x - 1, quote.y), AmmError::SlippageExceeded);
+    fixture.deposit(lp, gross_x, quote.y).unwrap();
+
+    assert_eq!(fixture.user_balance(&mint_x), user_x - gross_x);
+    assert_eq!(fixture.vault_balance(&mint_x), vault_x + quote.x);
+    assert_eq!(fixture.vault_balance(&mint_y), INITIAL_Y + quote.y);
+    assert_eq!(fixture.user_balance(&mint_lp), user_lp + lp);
+}
+
+#[test]
+fn swap_prices_the_input_net_of_the_transfer_fee() {
+    let mut fixture = funded_pool();
+    let (mint_x, mint_y) = (fixture.mint_x, fixture.mint_y);
+    let amount = 10_000_000;
+
+    let amount_in = amount - transfer_fee().calculate_fee(amount).unwrap();
+    let out = quote_pool(&fixture).swap(true, amount_in).unwrap().withdraw;
+    let (user_x, user_y, vault_x) = (fixture.user_balance(&mint_x), fixture.user_balance(&mint_y), fixture.vault_balance(&mint_x));
+
+    assert_amm_error(fixture.swap(true, amount, out + 1), AmmError::SlippageExceeded);
+    fixture.swap(true, amount, out).unwrap();
+
+    assert_eq!(fixture.user_balance(&mint_x), user_x - amount);
+    assert_eq!(fixture.vault_balance(&mint_x), vault_x + amount_in);
+    assert_eq!(fixture.user_balance(&mint_y), user_y + out);
+}
+
+#[test]
+fn swap_bounds_the_output_net_of_the_transfer_fee() {
+    let mut fixture = funded_pool();
+    let (mint_x, mint_y) = (fixture.mint_x, fixture.mint_y);
+    let amount = 40_000_000;
+
+    let out = quote_pool(&fixture).swap(false, amount).unwrap().withdraw;
+    let received = out - transfer_fee().calculate_fee(out).unwrap();
+    let (user_x, user_y, vault_x) = (fixture.user_balance(&mint_x), fixture.user_balance(&mint_y), fixture.vault_balance(&mint_x));
+
+    assert_amm_error(fixture.swap(false, amount, received + 1), AmmError::SlippageExceeded);
+    fixture.swap(false, amount, received).unwrap();
+
+    assert_eq!(fixture.user_balance(&mint_y), user_y - amount);
+    assert_eq!(fixture.vault_balance(&mint_x), vault_x - out);
+    assert_eq!(fixture.user_balance(&mint_x), user_x + received);
+}
+
+#[test]
+fn withdraw_bounds_the_amounts_net_of_the_transfer_fee() {
+    let mut fixture = funded_pool();
+    let (mint_x, mint_y, mint_lp) = (fixture.mint_x, fixture.mint_y, fixture.mint_lp);
+    let lp = fixture.user_balance(&mint_lp) / 2;
+
+    let quote = quote_pool(&fixture).withdraw(lp).unwrap();
+    let received_x = quote.x - transfer_fee().calculate_fee(quote.x).unwrap();
+    let (user_x, user_y, vault_x) = (fixture.user_balance(&mint_x), fixture.user_balance(&mint_y), fixture.vault_balance(&mint_x));
+
+    assert_amm_error(fixture.withdraw(lp, received_x + 1, quote.y), AmmError::SlippageExceeded);
+    fixture.withdraw(lp, received_x, quote.y).unwrap();
+
+    assert_eq!(fixture.vault_balance(&mint_x), vault_x - quote.x);
+    assert_eq!(fixture.user_balance(&mint_x), user_x + received_x);
+    assert_eq!(fixture.user_balance(&mint_y), user_y + quote.y);
+}
+
+#[test]
+fn flash_repay_covers_the_transfer_fee() {
+    let mut fixture = funded_pool();
+    let (pool, mint_x) = (fixture.pool(), fixture.mint_x);
+    let amount = 100_000_000;
+
+    let owed = amount + (amount * FEES as u64).div_ceil(10_000);
+    let fee = transfer_fee();
+    let paid = owed + fee.calculate_inverse_fee(owed).unwrap();
+    let received = amount - fee.calculate_fee(amount).unwrap();
+    let (user_x, vault_x) = (fixture.user_balance(&mint_x), fixture.vault_balance(&mint_x));
+
+    let ixs = [fixture.flash_borrow_ix(&pool, &mint_x, amount), fixture.flash_repay_ix(&pool, &mint_x)];
+    fixture.send_instructions(&ixs, Payer::User).unwrap();
+
+    // The vault gets back exactly what is owed, however much the user paid
+    // in transfer fees on the way.
+    assert_eq!(fixture.vault_balance(&mint_x), vault_x - amount + owed);
+    assert_eq!(fixture.user_balance(&mint_x), user_x + received - paid);
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use amm_quote::QuoteError;
+
+#[error_code]
+pub enum AmmError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Slippage limit exceeded")]
+    SlippageExceeded,
+    #[msg("Pool has no liquidity")]
+    NoLiquidity,
+    #[msg("Initial deposit is below the minimum liquidity")]
+    InsufficientInitialLiquidity,
+    #[msg("Insufficient balance")]
+    InsufficientBalance,
+    #[msg("Curve calculation failed")]
+    CurveError,
+    #[msg("Invalid precision")]
+    InvalidPrecision,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Arithmetic underflow")]
+    Underflow,
+    #[msg("Pool is locked")]
+    PoolLocked,
+    #[msg("Signer is not the pool authority")]
+    Unauthorized,
+    #[msg("Fees must not exceed 10000 basis points")]
+    InvalidFee,
+    #[msg("Invalid amplification coefficient")]
+    InvalidAmp,
+    #[msg("Invalid amplification ramp")]
+    InvalidAmpRamp,
+    #[msg("Pool does not use the StableSwap curve")]
+    NotStableSwap,
+    #[msg("Mint X and mint Y must differ")]
+    IdenticalMints,
+    #[msg("Invalid swap route")]
+    InvalidRoute,
+    #[msg("Vault is not the pool's associated token account")]
+    InvalidVault,
+    #[msg("A flash loan is outstanding on this pool")]
+    FlashLoanActive,
+    #[msg("No flash loan to repay")]
+    NoFlashLoan,
+    #[msg("Flash borrow must be followed by a flash repay")]
+    MissingFlashRepay,
+    #[msg("Invalid tick or tick range")]
+    InvalidTick,
+    #[msg("Sqrt price is out of range")]
+    InvalidPrice,
+    #[msg("Pool has reached its initialized tick limit")]
+    TooManyTicks,
+    #[msg("Position still holds liquidity or uncollected fees")]
+    PositionNotEmpty,
+    #[msg("Deposit would take the pool above its reserve cap")]
+    DepositCapExceeded,
+    #[msg("Wallet is not on the pool's LP allowlist")]
+    NotAllowlisted,
+    #[msg("Only pools with no authority and no access restrictions can be registered")]
+    PoolNotRegistrable,
+    #[msg("Position liquidity is below the pool minimum")]
+    PositionTooSmall,
+    #[msg("Protocol fees must be collected before renouncing the authority")]
+    ProtocolFeesUncollected,
+}
+
+impl From<QuoteError> for AmmError {
+    fn from(error: QuoteError) -> AmmError {
+        match error {
+            QuoteError::ZeroAmount => AmmError::ZeroAmount,
+            QuoteError::NoLiquidity => AmmError::NoLiquidity,
+            QuoteError::InsufficientBalance => AmmError::InsufficientBalance,
+            QuoteError::InsufficientInitialLiquidity => AmmError::InsufficientInitialLiquidity,
+            QuoteError::InvalidFee => AmmError::InvalidFee,
+            QuoteError::Overflow => AmmError::Overflow,
+            QuoteError::Underflow => AmmError::Underflow,
+            QuoteError::NotConverged => AmmError::CurveError,
+            QuoteError::InvalidTick => AmmError::InvalidTick,
+            QuoteError::InvalidPrice => AmmError::InvalidPrice,
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{CurveType, DynamicFeeParams};
+
+#[event]
+pub struct PoolInitialized {
+    pub config: Pubkey,
+    pub seed: u64,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub mint_lp: Pubkey,
+    pub fees: u16,
+    pub curve: CurveType,
+    pub authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct PoolRegistered {
+    pub config: Pubkey,
+    pub registry: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub fees: u16,
+    pub curve: CurveType,
+}
+
+#[event]
+pub struct LiquidityAdded {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub lp_amount: u64,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub lp_supply_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+    pub lp_supply_after: u64,
+}
+
+#[event]
+pub struct LiquidityRemoved {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub lp_amount: u64,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub lp_supply_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+    pub lp_supply_after: u64,
+}
+
+#[event]
+pub struct Swapped {
+    pub config: Pubkey,
+    pub user: Pubkey,
+    pub is_x: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+    pub protocol_fee: u64,
+    /// Fee rate charged, in basis points.
+    pub fees: u16,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+    pub lp_supply: u64,
+}
+
+#[event]
+pub struct RouteHopSwapped {
+    pub config: Pubkey,
+    pub hop: u8,
+    pub mint_in: Pubkey,
+    pub mint_out: Pubkey,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fees: u16,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+    pub lp_supply: u64,
+}
+
+#[event]
+pub struct PoolLocked {
+    pub config: Pubkey,
+    pub locked: bool,
+}
+
+#[event]
+pub struct FeesUpdated {
+    pub config: Pubkey,
+    pub fees: u16,
+    pub protocol_fee: u16,
+}
+
+#[event]
+pub struct AmpRampUpdated {
+    pub config: Pubkey,
+    pub amp_initial: u64,
+    pub amp_target: u64,
+    pub amp_ramp_start: i64,
+    pub amp_ramp_end: i64,
+}
+
+#[event]
+pub struct AuthorityUpdated {
+    pub config: Pubkey,
+    pub authority: Option<Pubkey>,
+}
+
+#[event]
+pub struct DepositCapsUpdated {
+    pub config: Pubkey,
+    pub max_reserve_x: u64,
+    pub max_reserve_y: u64,
+}
+
+#[event]
+pub struct LpAllowlistUpdated {
+    pub config: Pubkey,
+    pub root: Option<[u8; 32]>,
+}
+
+#[event]
+pub struct DynamicFeeUpdated {
+    pub config: Pubkey,
+    pub dynamic_fee: Option<DynamicFeeParams>,
+}
+
+#[event]
+pub struct ProtocolFeesCollected {
+    pub config: Pubkey,
+    pub treasury: Pubkey,
+    pub amount_x: u64,
+    pub amount_y: u64,
+}
+
+#[event]
+pub struct FlashBorrowed {
+    pub config: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub owed: u64,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+    pub lp_supply: u64,
+}
+
+#[event]
+pub struct FlashRepaid {
+    pub config: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub reserve_x_before: u64,
+    pub reserve_y_before: u64,
+    pub reserve_x_after: u64,
+    pub reserve_y_after: u64,
+    pub lp_supply: u64,
+}
+
+#[event]
+pub struct ConcentratedPoolInitialized {
+    pub pool: Pubkey,
+    pub seed: u64,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub fees: u16,
+    pub tick_spacing: u16,
+    pub min_position_liquidity: u128,
+    pub sqrt_price: u128,
+    pub tick: i32,
+}
+
+#[event]
+pub struct PositionOpened {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+}
+
+#[event]
+pub struct PositionLiquidityUpdated {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub liquidity_delta: i128,
+    pub amount_x: u64,
+    pub amount_y: u64,
+    pub position_liquidity: u128,
+    pub pool_liquidity: u128,
+}
+
+#[event]
+pub struct PositionFeesCollected {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+    pub amount_x: u64,
+    pub amount_y: u64,
+}
+
+#[event]
+pub struct PositionClosed {
+    pub pool: Pubkey,
+    pub position: Pubkey,
+    pub owner: Pubkey,
+}
+
+#[event]
+pub struct ConcentratedSwapped {
+    pub pool: Pubkey,
+    pub user: Pubkey,
+    pub is_x: bool,
+    pub amount_in: u64,
+    pub amount_out: u64,
+    pub fee: u64,
+    pub sqrt_price: u128,
+    pub tick: i32,
+    pub liquidity: u128,
+}
@@ -1,57 +1,84 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount, Transfer, transfer, MintTo, mint_to}
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked, MintTo, mint_to}
 };
-use constant_product_curve::ConstantProduct;
+use amm_quote::Pool;
 
-use crate::state::Config;
+use crate::{
+    error::AmmError,
+    events::LiquidityAdded,
+    state::Config,
+    utils::{amount_after_transfer_fee, amount_with_transfer_fee},
+};
 
 
 #[derive(Accounts)]
 pub struct Deposit<'info> {
     #[account(mut)]
     pub user: Signer<'info>,
-    pub mint_x: Account<'info, Mint>,
-    pub mint_y: Account<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program_x
+    )]
+    pub mint_x: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program_y
+    )]
+    pub mint_y: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         seeds = [b"lp", config.key().as_ref()],
         bump = config.lp_bump,
+        mint::token_program = token_program_lp,
     )]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
 
     #[account(
         mut,
         associated_token::mint = mint_x,
         associated_token::authority = config,
+        associated_token::token_program = token_program_x,
     )]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = mint_y,
         associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_lp,
     )]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_lp: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         mut,
         associated_token::mint = mint_x,
         associated_token::authority = user,
+        associated_token::token_program = token_program_x,
     )]
-    pub user_x: Account<'info, TokenAccount>,
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
     
     #[account(
         mut,
         associated_token::mint = mint_y,
         associated_token::authority = user,
+        associated_token::token_program = token_program_y,
     )]
-    pub user_y: Account<'info, TokenAccount>,
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
 
 
     #[account(
+        mut,
         has_one = mint_x,
         has_one = mint_y,
         seeds = [b"config", config.seed.to_le_bytes().as_ref()],
@@ -64,67 +91,202 @@ pub struct Deposit<'info> {
         payer = user,
         associated_token::mint = mint_lp,
         associated_token::authority = user,
+        associated_token::token_program = token_program_lp,
     )]
-    pub user_lp: Account<'info, TokenAccount>,
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program_x: Interface<'info, TokenInterface>,
+    pub token_program_y: Interface<'info, TokenInterface>,
+    pub token_program_lp: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Deposit<'info> {
-    pub fn deposit(&mut self, amount: u64, max_x: u64, max_y: u64) -> Result<()> {
-        assert!(amount != 0);
+    /// Mints `amount` LP tokens for a proportional share of both reserves.
+    ///
+    /// On the first deposit the pool takes exactly `max_x` and `max_y`, mints
+    /// `sqrt(x * y)` LP tokens and locks `Config::MINIMUM_LIQUIDITY` of them in
+    /// `vault_lp`; `amount` is then the minimum the depositor accepts.
+    ///
+    /// `proof` is only read when the pool has an LP allowlist.
+    pub fn deposit(&mut self, amount: u64, max_x: u64, max_y: u64, proof: &[[u8; 32]]) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(self.config.flash_loan_owed == 0, AmmError::FlashLoanActive);
+        require!(amount != 0, AmmError::ZeroAmount);
+        self.config.check_lp_allowlist(&self.user.key(), proof)?;
+
+        let pool = self.config.pool(
+            self.vault_x.amount,
+            self.vault_y.amount,
+            self.mint_lp.supply,
+            Clock::get()?.unix_timestamp,
+        )?;
+        let (reserve_x, reserve_y) = (pool.reserve_x, pool.reserve_y);
+        self.config.update_oracle(reserve_x, reserve_y)?;
 
-        let (x,y) = match self.mint_lp.supply == 0 && self.vault_x.amount == 0 {
-            true => (max_x, max_y),
+        // Keyed on supply alone: tokens sent straight to the vaults can't make
+        // the pool look funded, and `MINIMUM_LIQUIDITY` keeps supply from ever
+        // returning to zero.
+        let is_first_deposit = self.mint_lp.supply == 0;
+
+        // Amounts the user sends, including any Token-2022 transfer fee, so the
+        // vaults receive exactly what the curve asks for.
+        let (x, y, lp) = match is_first_deposit {
+            true => {
+                let quote = Pool::initial_deposit(
+                    amount_after_transfer_fee(&self.mint_x, max_x)?,
+                    amount_after_transfer_fee(&self.mint_y, max_y)?,
+                ).map_err(AmmError::from)?;
+                require!(quote.lp >= amount, AmmError::SlippageExceeded);
+
+                (max_x, max_y, quote.lp)
+            }
             false => {
-                let amounts = ConstantProduct::xy_deposit_amounts_from_l(
-                    self.vault_x.amount, 
-                    self.vault_y.amount, 
-                    self.mint_lp.supply, 
-                    amount, 
-                    6
-                ).unwrap();
-
-                (amounts.x, amounts.y)
+                let quote = pool.deposit(amount).map_err(AmmError::from)?;
+
+                (
+                    amount_with_transfer_fee(&self.mint_x, quote.x)?,
+                    amount_with_transfer_fee(&self.mint_y, quote.y)?,
+                    amount,
+                )
             }
         };
 
-        assert!(x <= max_x && y <=max_y);
+        require!(x <= max_x && y <= max_y, AmmError::SlippageExceeded);
         self.deposit_token(true, x)?;
         self.deposit_token(false, y)?;
-        self.mint_lp_token(amount)?;
+
+        if is_first_deposit {
+            self.lock_minimum_liquidity()?;
+        }
+        self.mint_lp_token(lp)?;
+
+        let lp_supply_before = self.mint_lp.supply;
+        self.vault_x.reload()?;
+        self.vault_y.reload()?;
+        self.mint_lp.reload()?;
+        let (reserve_x_after, reserve_y_after) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        self.config.check_deposit_caps(reserve_x_after, reserve_y_after)?;
+
+        emit!(LiquidityAdded {
+            config: self.config.key(),
+            user: self.user.key(),
+            lp_amount: lp,
+            reserve_x_before: reserve_x,
+            reserve_y_before: reserve_y,
+            lp_supply_before,
+            reserve_x_after,
+            reserve_y_after,
+            lp_supply_after: self.mint_lp.supply,
+        });
 
         Ok(())
     }
 
-    pub fn deposit_token(&self, is_x: bool, amount: u64) -> Result<()> {
-        let (from,to) = match is_x {
-            true => (self.user_x.to_account_info(), self.vault_x.to_account_info()),
-            false => (self.user_y.to_account_info(), self.vault_y.to_account_info()),
+    /// Deposits only one side of the pair. The optimal part of `amount` is
+    /// swapped through the pool's own curve and the rest is added as
+    /// liquidity together with the swap output, which never leaves the vault.
+    /// The split is optimal for the constant-product curve; on StableSwap
+    /// pools it is an approximation and the unmatched remainder accrues to LPs.
+    pub fn deposit_single(&mut self, is_x: bool, amount: u64, min_lp_out: u64, proof: &[[u8; 32]]) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(self.config.flash_loan_owed == 0, AmmError::FlashLoanActive);
+        require!(amount != 0, AmmError::ZeroAmount);
+        self.config.check_lp_allowlist(&self.user.key(), proof)?;
+        require!(self.mint_lp.supply != 0, AmmError::NoLiquidity);
+
+        let now = Clock::get()?.unix_timestamp;
+        let pool = self.config.pool(
+            self.vault_x.amount,
+            self.vault_y.amount,
+            self.mint_lp.supply,
+            now,
+        )?;
+        let (reserve_x, reserve_y) = (pool.reserve_x, pool.reserve_y);
+        self.config.update_oracle(reserve_x, reserve_y)?;
+
+        let mint_in = match is_x {
+            true => &self.mint_x,
+            false => &self.mint_y,
         };
 
-        let cpi_program = self.token_program.to_account_info();
+        let amount_in = amount_after_transfer_fee(mint_in, amount)?;
+        let (swap, lp) = pool.deposit_single(is_x, amount_in).map_err(AmmError::from)?;
+        require!(lp >= min_lp_out, AmmError::SlippageExceeded);
+
+        self.config.accrue_protocol_fee(is_x, swap.protocol_fee)?;
+
+        self.deposit_token(is_x, amount)?;
+        self.mint_lp_token(lp)?;
+
+        let lp_supply_before = self.mint_lp.supply;
+        self.vault_x.reload()?;
+        self.vault_y.reload()?;
+        self.mint_lp.reload()?;
+        let (reserve_x_after, reserve_y_after) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+        self.config.check_deposit_caps(reserve_x_after, reserve_y_after)?;
+        // The swap leg moves the price like any other swap.
+        self.config.update_volatility((reserve_x, reserve_y), (reserve_x_after, reserve_y_after), now)?;
+
+        emit!(LiquidityAdded {
+            config: self.config.key(),
+            user: self.user.key(),
+            lp_amount: lp,
+            reserve_x_before: reserve_x,
+            reserve_y_before: reserve_y,
+            lp_supply_before,
+            reserve_x_after,
+            reserve_y_after,
+            lp_supply_after: self.mint_lp.supply,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_token(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (
+                self.user_x.to_account_info(),
+                self.vault_x.to_account_info(),
+                &self.mint_x,
+                self.token_program_x.to_account_info(),
+            ),
+            false => (
+                self.user_y.to_account_info(),
+                self.vault_y.to_account_info(),
+                &self.mint_y,
+                self.token_program_y.to_account_info(),
+            ),
+        };
 
-        let cpi_accounts = Transfer{
+        let cpi_accounts = TransferChecked{
             from,
+            mint: mint.to_account_info(),
             to,
             authority: self.user.to_account_info()
         };
 
-        let ctx: CpiContext<'_, '_, '_, '_, Transfer<'_>> = CpiContext::new(cpi_program, cpi_accounts);
+        let ctx = CpiContext::new(cpi_program, cpi_accounts);
 
-        transfer(ctx, amount)?;
+        transfer_checked(ctx, amount, mint.decimals)?;
         Ok(())
     }
 
     pub fn mint_lp_token(&self, amount: u64) -> Result<()> {
-        let cpi_program = self.token_program.to_account_info();
+        self.mint_lp_to(self.user_lp.to_account_info(), amount)
+    }
+
+    pub fn lock_minimum_liquidity(&self) -> Result<()> {
+        self.mint_lp_to(self.vault_lp.to_account_info(), Config::MINIMUM_LIQUIDITY)
+    }
+
+    fn mint_lp_to(&self, to: AccountInfo<'info>, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program_lp.to_account_info();
 
         let cpi_accoutns = MintTo{
             mint: self.mint_lp.to_account_info(),
-            to: self.user_lp.to_account_info(),
+            to,
             authority: self.config.to_account_info(),
         };
 
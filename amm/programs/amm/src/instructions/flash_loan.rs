@@ -0,0 +1,234 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::instructions::{load_current_index_checked, load_instruction_at_checked, ID as INSTRUCTIONS_SYSVAR_ID},
+    Discriminator,
+};
+use anchor_spl::{
+    associated_token::{get_associated_token_address_with_program_id, AssociatedToken},
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked}
+};
+
+use crate::{
+    error::AmmError,
+    events::{FlashBorrowed, FlashRepaid},
+    instruction,
+    state::Config,
+    utils::amount_with_transfer_fee,
+};
+
+
+/// Shared by `flash_borrow` and `flash_repay`. The position of `config` is
+/// relied upon when matching the repay instruction, see `FlashLoan::CONFIG_INDEX`.
+#[derive(Accounts)]
+pub struct FlashLoan<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        constraint = mint.key() == config.mint_x || mint.key() == config.mint_y @ AmmError::InvalidVault,
+        mint::token_program = token_program,
+    )]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = config,
+        associated_token::token_program = token_program,
+    )]
+    pub vault: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = user,
+        associated_token::token_program = token_program,
+    )]
+    pub user_token: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        seeds = [b"lp", config.key().as_ref()],
+        bump = config.lp_bump,
+    )]
+    pub mint_lp: InterfaceAccount<'info, Mint>,
+
+    /// The pool's vault for the other side of the pair, read for the reserves
+    /// reported in events.
+    #[account(
+        constraint = other_vault.mint != mint.key()
+            && (other_vault.mint == config.mint_x || other_vault.mint == config.mint_y)
+            && other_vault.key() == get_associated_token_address_with_program_id(
+                &config.key(),
+                &other_vault.mint,
+                other_vault.to_account_info().owner,
+            ) @ AmmError::InvalidVault,
+    )]
+    pub other_vault: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: address is checked against the instructions sysvar id
+    #[account(address = INSTRUCTIONS_SYSVAR_ID)]
+    pub instructions: UncheckedAccount<'info>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> FlashLoan<'info> {
+    pub const CONFIG_INDEX: usize = 4;
+
+    /// Lends `amount` out of the vault. The transaction must contain a later
+    /// `flash_repay` for the same pool, which returns `amount` plus
+    /// `Config::fees` basis points; the fee stays in the vault for LPs.
+    ///
+    /// The repay is looked up in the instructions sysvar, which only lists
+    /// top-level instructions. A program borrowing through CPI therefore
+    /// can't repay from inside the same instruction; the transaction still
+    /// needs its own later top-level `flash_repay`.
+    pub fn flash_borrow(&mut self, amount: u64) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(self.config.flash_loan_owed == 0, AmmError::FlashLoanActive);
+        require!(amount != 0, AmmError::ZeroAmount);
+
+        let is_x = self.mint.key() == self.config.mint_x;
+        let protocol_fees = match is_x {
+            true => self.config.protocol_fees_x,
+            false => self.config.protocol_fees_y,
+        };
+        let reserve = self.vault.amount.checked_sub(protocol_fees).ok_or(AmmError::Underflow)?;
+        require!(amount <= reserve, AmmError::InsufficientBalance);
+
+        self.check_repay_instruction()?;
+
+        let fee = (amount as u128)
+            .checked_mul(self.config.fees as u128)
+            .ok_or(AmmError::Overflow)?
+            .div_ceil(Config::MAX_FEES as u128) as u64;
+
+        self.config.flash_loan_owed = amount.checked_add(fee).ok_or(AmmError::Overflow)?;
+        self.config.flash_loan_is_x = is_x;
+
+        let (reserve_x_before, reserve_y_before) = self.reserves()?;
+        self.withdraw_token(amount)?;
+        self.vault.reload()?;
+        let (reserve_x_after, reserve_y_after) = self.reserves()?;
+
+        emit!(FlashBorrowed {
+            config: self.config.key(),
+            mint: self.mint.key(),
+            amount,
+            owed: self.config.flash_loan_owed,
+            reserve_x_before,
+            reserve_y_before,
+            reserve_x_after,
+            reserve_y_after,
+            lp_supply: self.mint_lp.supply,
+        });
+
+        Ok(())
+    }
+
+    pub fn flash_repay(&mut self) -> Result<()> {
+        let owed = self.config.flash_loan_owed;
+        require!(owed != 0, AmmError::NoFlashLoan);
+        require!(
+            (self.mint.key() == self.config.mint_x) == self.config.flash_loan_is_x,
+            AmmError::InvalidVault
+        );
+
+        self.config.flash_loan_owed = 0;
+
+        // The vault must receive the full amount owed, net of any Token-2022
+        // transfer fee.
+        let amount = amount_with_transfer_fee(&self.mint, owed)?;
+
+        let (reserve_x_before, reserve_y_before) = self.reserves()?;
+        self.deposit_token(amount)?;
+        self.vault.reload()?;
+        let (reserve_x_after, reserve_y_after) = self.reserves()?;
+
+        emit!(FlashRepaid {
+            config: self.config.key(),
+            mint: self.mint.key(),
+            amount: owed,
+            reserve_x_before,
+            reserve_y_before,
+            reserve_x_after,
+            reserve_y_after,
+            lp_supply: self.mint_lp.supply,
+        });
+
+        Ok(())
+    }
+
+    /// Pool reserves, net of protocol fees, as the vaults stand.
+    fn reserves(&self) -> Result<(u64, u64)> {
+        match self.mint.key() == self.config.mint_x {
+            true => self.config.reserves(self.vault.amount, self.other_vault.amount),
+            false => self.config.reserves(self.other_vault.amount, self.vault.amount),
+        }
+    }
+
+    /// Looks for a top-level `flash_repay` on the same pool after the current
+    /// instruction.
+    fn check_repay_instruction(&self) -> Result<()> {
+        let instructions = self.instructions.to_account_info();
+        let current = load_current_index_checked(&instructions)? as usize;
+
+        let mut index = current + 1;
+        while let Ok(ix) = load_instruction_at_checked(index, &instructions) {
+            if ix.program_id == crate::ID
+                && ix.data.get(..8) == Some(instruction::FlashRepay::DISCRIMINATOR)
+                && ix.accounts.get(Self::CONFIG_INDEX).map(|meta| meta.pubkey) == Some(self.config.key())
+            {
+                return Ok(());
+            }
+            index += 1;
+        }
+
+        err!(AmmError::MissingFlashRepay)
+    }
+
+    pub fn deposit_token(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked{
+            from: self.user_token.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.vault.to_account_info(),
+            authority: self.user.to_account_info()
+        };
+
+        let ctx = CpiContext::new(self.token_program.to_account_info(), cpi_accounts);
+
+        transfer_checked(ctx, amount, self.mint.decimals)?;
+        Ok(())
+    }
+
+    pub fn withdraw_token(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked{
+            from: self.vault.to_account_info(),
+            mint: self.mint.to_account_info(),
+            to: self.user_token.to_account_info(),
+            authority: self.config.to_account_info()
+        };
+
+        let seeds = &[
+            &b"config"[..],
+            &self.config.seed.to_le_bytes(),
+            &[self.config.config_bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, self.mint.decimals)?;
+        Ok(())
+    }
+}
@@ -1,44 +1,70 @@
+use amm_quote::stable_swap;
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{Mint, Token, TokenAccount}
+    token_interface::{Mint, TokenAccount, TokenInterface}
+};
+use crate::{
+    error::AmmError,
+    events::PoolInitialized,
+    instructions::record_pool,
+    state::{Config, CurveType, PoolRegistry},
 };
-use crate::state::Config;
 
 
 #[derive(Accounts)]
-#[instruction(seed: u64)]
+#[instruction(seed: u64, fees: u16, lp_decimals: u8, authority: Option<Pubkey>, curve: CurveType)]
 pub struct Initialize<'info> {
     #[account(mut)]
     pub initializer: Signer<'info>,
-    pub mint_x: Account<'info, Mint>,
-    pub mint_y: Account<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program_x
+    )]
+    pub mint_x: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program_y
+    )]
+    pub mint_y: InterfaceAccount<'info, Mint>,
 
     #[account(
         init,
         payer = initializer,
         seeds = [b"lp", config.key().as_ref()],
         bump,
-        mint::decimals = 6,
+        mint::decimals = lp_decimals,
         mint:: authority = config,
+        mint::token_program = token_program_lp,
     )]
-    pub mint_lp: Account<'info, Mint>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
 
     #[account(
         init,
         payer = initializer,
         associated_token::mint = mint_x,
         associated_token::authority = config,
+        associated_token::token_program = token_program_x,
     )]
-    pub vault_x: Account<'info, TokenAccount>,
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
 
     #[account(
         init,
         payer = initializer,
         associated_token::mint = mint_y,
         associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_lp,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_lp,
     )]
-    pub vault_y: Account<'info, TokenAccount>,
+    pub vault_lp: InterfaceAccount<'info, TokenAccount>,
 
     //Rust Code
     #[account(
@@ -50,25 +76,96 @@ pub struct Initialize<'info> {
     )]
     pub config: Account<'info, Config>,
 
+    /// Canonical registry entry for the pair, fee tier and curve. Passing it
+    /// claims the entry for this pool, see `RegisterPool::register_pool`.
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [
+            b"registry",
+            PoolRegistry::sorted(mint_x.to_account_info().key, mint_y.to_account_info().key).0.as_ref(),
+            PoolRegistry::sorted(mint_x.to_account_info().key, mint_y.to_account_info().key).1.as_ref(),
+            fees.to_le_bytes().as_ref(),
+            &[curve as u8],
+        ],
+        bump,
+        space = 8 + PoolRegistry::INIT_SPACE
+    )]
+    pub registry: Option<Account<'info, PoolRegistry>>,
+
     pub associated_token_program: Program<'info, AssociatedToken>,
-    pub token_program: Program<'info, Token>,
+    pub token_program_x: Interface<'info, TokenInterface>,
+    pub token_program_y: Interface<'info, TokenInterface>,
+    pub token_program_lp: Interface<'info, TokenInterface>,
     pub system_program: Program<'info, System>,
 }
 
 impl<'info> Initialize<'info> {
-    pub fn initialize(&mut self, seed: u64, fees: u16, authority: Option<Pubkey>, bumps: &InitializeBumps) -> Result<()> {
-        
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize(
+        &mut self,
+        seed: u64,
+        fees: u16,
+        lp_decimals: u8,
+        authority: Option<Pubkey>,
+        curve: CurveType,
+        amp: u64,
+        bumps: &InitializeBumps,
+    ) -> Result<()> {
+        require!(fees <= Config::MAX_FEES, AmmError::InvalidFee);
+        if curve == CurveType::StableSwap {
+            require!((stable_swap::MIN_AMP..=stable_swap::MAX_AMP).contains(&amp), AmmError::InvalidAmp);
+        }
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(lp_decimals <= Config::MAX_LP_DECIMALS, AmmError::InvalidPrecision);
+        require_keys_neq!(self.mint_x.key(), self.mint_y.key(), AmmError::IdenticalMints);
+
         self.config.set_inner(Config { 
             seed, 
             authority, 
             mint_x: self.mint_x.key(), 
             mint_y: self.mint_y.key(), 
             fees, 
+            curve,
+            amp_initial: amp,
+            amp_target: amp,
+            amp_ramp_start: now,
+            amp_ramp_end: now,
+            protocol_fee: 0,
+            protocol_fees_x: 0,
+            protocol_fees_y: 0,
+            price_x_cumulative: 0,
+            price_y_cumulative: 0,
+            last_update: now,
+            flash_loan_owed: 0,
+            flash_loan_is_x: false,
+            max_reserve_x: Config::UNCAPPED,
+            max_reserve_y: Config::UNCAPPED,
+            lp_allowlist: None,
+            dynamic_fee: None,
+            volatility: 0,
+            volatility_updated_at: now,
             locked: false, 
             config_bump: bumps.config, 
             lp_bump:  bumps.mint_lp
         });
 
+        emit!(PoolInitialized {
+            config: self.config.key(),
+            seed,
+            mint_x: self.mint_x.key(),
+            mint_y: self.mint_y.key(),
+            mint_lp: self.mint_lp.key(),
+            fees,
+            curve,
+            authority,
+        });
+
+        if let (Some(registry), Some(bump)) = (self.registry.as_mut(), bumps.registry) {
+            record_pool(registry, &self.config, bump)?;
+        }
+
         Ok(())
     }
 }
\ No newline at end of file
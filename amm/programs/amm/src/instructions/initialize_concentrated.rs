@@ -0,0 +1,118 @@
+use amm_quote::concentrated::{self, MAX_SQRT_PRICE, MIN_SQRT_PRICE};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface}
+};
+
+use crate::{
+    error::AmmError,
+    events::ConcentratedPoolInitialized,
+    state::{Config, ConcentratedPool},
+};
+
+
+#[derive(Accounts)]
+#[instruction(seed: u64)]
+pub struct InitializeConcentrated<'info> {
+    #[account(mut)]
+    pub initializer: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program_x
+    )]
+    pub mint_x: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program_y
+    )]
+    pub mint_y: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_x,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = initializer,
+        associated_token::mint = mint_y,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init,
+        payer = initializer,
+        seeds = [b"concentrated", seed.to_le_bytes().as_ref()],
+        bump,
+        space = 8 + ConcentratedPool::INIT_SPACE
+    )]
+    pub pool: Account<'info, ConcentratedPool>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program_x: Interface<'info, TokenInterface>,
+    pub token_program_y: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> InitializeConcentrated<'info> {
+    /// Opens an empty concentrated-liquidity pool at `sqrt_price` (Q64.64).
+    /// Positions may only start and end on multiples of `tick_spacing`, and
+    /// must hold no liquidity or at least `min_position_liquidity`.
+    pub fn initialize_concentrated(
+        &mut self,
+        seed: u64,
+        fees: u16,
+        tick_spacing: u16,
+        min_position_liquidity: u128,
+        sqrt_price: u128,
+        bumps: &InitializeConcentratedBumps,
+    ) -> Result<()> {
+        require!(fees < Config::MAX_FEES, AmmError::InvalidFee);
+        require!(
+            tick_spacing != 0 && tick_spacing <= ConcentratedPool::MAX_TICK_SPACING,
+            AmmError::InvalidTick
+        );
+        require!(min_position_liquidity != 0, AmmError::ZeroAmount);
+        require!((MIN_SQRT_PRICE..MAX_SQRT_PRICE).contains(&sqrt_price), AmmError::InvalidPrice);
+        require_keys_neq!(self.mint_x.key(), self.mint_y.key(), AmmError::IdenticalMints);
+
+        let tick = concentrated::tick_at_sqrt_price(sqrt_price).map_err(AmmError::from)?;
+
+        self.pool.set_inner(ConcentratedPool {
+            seed,
+            mint_x: self.mint_x.key(),
+            mint_y: self.mint_y.key(),
+            fees,
+            tick_spacing,
+            min_position_liquidity,
+            sqrt_price,
+            tick,
+            liquidity: 0,
+            fee_growth_global_x: 0,
+            fee_growth_global_y: 0,
+            ticks: Vec::new(),
+            bump: bumps.pool,
+        });
+
+        emit!(ConcentratedPoolInitialized {
+            pool: self.pool.key(),
+            seed,
+            mint_x: self.mint_x.key(),
+            mint_y: self.mint_y.key(),
+            fees,
+            tick_spacing,
+            min_position_liquidity,
+            sqrt_price,
+            tick,
+        });
+
+        Ok(())
+    }
+}
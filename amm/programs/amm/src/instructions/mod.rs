@@ -1,5 +1,27 @@
 pub mod initialize;
+pub mod register_pool;
 pub mod deposit;
+pub mod swap;
+pub mod swap_route;
+pub mod withdraw;
+pub mod flash_loan;
+pub mod update_config;
+pub mod collect_protocol_fees;
+pub mod initialize_concentrated;
+pub mod position;
+pub mod modify_position;
+pub mod swap_concentrated;
 
 pub use initialize::*;
-pub use deposit::*;
\ No newline at end of file
+pub use register_pool::*;
+pub use deposit::*;
+pub use swap::*;
+pub use swap_route::*;
+pub use withdraw::*;
+pub use flash_loan::*;
+pub use update_config::*;
+pub use collect_protocol_fees::*;
+pub use initialize_concentrated::*;
+pub use position::*;
+pub use modify_position::*;
+pub use swap_concentrated::*;
\ No newline at end of file
@@ -0,0 +1,257 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked}
+};
+
+use crate::{
+    error::AmmError,
+    events::{PositionFeesCollected, PositionLiquidityUpdated},
+    state::{ConcentratedPool, Position},
+    utils::{amount_after_transfer_fee, amount_with_transfer_fee},
+};
+
+
+#[derive(Accounts)]
+pub struct ModifyPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program_x
+    )]
+    pub mint_x: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program_y
+    )]
+    pub mint_y: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [b"concentrated", pool.seed.to_le_bytes().as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, ConcentratedPool>,
+
+    #[account(
+        mut,
+        has_one = pool,
+        has_one = owner,
+        seeds = [
+            b"position",
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            position.tick_lower.to_le_bytes().as_ref(),
+            position.tick_upper.to_le_bytes().as_ref(),
+        ],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, Position>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint_x,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program_x,
+    )]
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        associated_token::mint = mint_y,
+        associated_token::authority = owner,
+        associated_token::token_program = token_program_y,
+    )]
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program_x: Interface<'info, TokenInterface>,
+    pub token_program_y: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> ModifyPosition<'info> {
+    /// Adds `liquidity` to the position, pulling at most `max_x` and `max_y`
+    /// from the owner.
+    pub fn increase_liquidity(&mut self, liquidity: u128, max_x: u64, max_y: u64) -> Result<()> {
+        require!(liquidity != 0, AmmError::ZeroAmount);
+
+        let delta = i128::try_from(liquidity).map_err(|_| AmmError::Overflow)?;
+        let (x, y) = self.pool.modify_position(&mut self.position, delta)?;
+
+        // Amounts the owner sends, including any Token-2022 transfer fee, so
+        // the vaults receive exactly what the range needs.
+        let x = amount_with_transfer_fee(&self.mint_x, x)?;
+        let y = amount_with_transfer_fee(&self.mint_y, y)?;
+        require!(x <= max_x && y <= max_y, AmmError::SlippageExceeded);
+
+        if x != 0 {
+            self.deposit_token(true, x)?;
+        }
+        if y != 0 {
+            self.deposit_token(false, y)?;
+        }
+
+        self.emit_liquidity_updated(delta, x, y);
+
+        Ok(())
+    }
+
+    /// Removes `liquidity` from the position and pays its share of the
+    /// reserves straight to the owner. Fees stay owed until `collect_fees`.
+    pub fn decrease_liquidity(&mut self, liquidity: u128, min_x: u64, min_y: u64) -> Result<()> {
+        require!(liquidity != 0, AmmError::ZeroAmount);
+        require!(self.position.liquidity >= liquidity, AmmError::InsufficientBalance);
+
+        let delta = i128::try_from(liquidity)
+            .map_err(|_| AmmError::Overflow)?
+            .checked_neg()
+            .ok_or(AmmError::Overflow)?;
+        let (x, y) = self.pool.modify_position(&mut self.position, delta)?;
+
+        // The slippage bounds apply to what the owner receives, net of any
+        // Token-2022 transfer fees.
+        require!(
+            amount_after_transfer_fee(&self.mint_x, x)? >= min_x
+                && amount_after_transfer_fee(&self.mint_y, y)? >= min_y,
+            AmmError::SlippageExceeded
+        );
+
+        if x != 0 {
+            self.withdraw_token(true, x)?;
+        }
+        if y != 0 {
+            self.withdraw_token(false, y)?;
+        }
+
+        self.emit_liquidity_updated(delta, x, y);
+
+        Ok(())
+    }
+
+    /// Pays out every fee the position has earned so far.
+    pub fn collect_fees(&mut self) -> Result<()> {
+        self.pool.modify_position(&mut self.position, 0)?;
+
+        let (x, y) = (self.position.tokens_owed_x, self.position.tokens_owed_y);
+        self.position.tokens_owed_x = 0;
+        self.position.tokens_owed_y = 0;
+
+        if x != 0 {
+            self.withdraw_token(true, x)?;
+        }
+        if y != 0 {
+            self.withdraw_token(false, y)?;
+        }
+
+        emit!(PositionFeesCollected {
+            pool: self.pool.key(),
+            position: self.position.key(),
+            owner: self.owner.key(),
+            amount_x: x,
+            amount_y: y,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_token(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (
+                self.user_x.to_account_info(),
+                self.vault_x.to_account_info(),
+                &self.mint_x,
+                self.token_program_x.to_account_info(),
+            ),
+            false => (
+                self.user_y.to_account_info(),
+                self.vault_y.to_account_info(),
+                &self.mint_y,
+                self.token_program_y.to_account_info(),
+            ),
+        };
+
+        let cpi_accounts = TransferChecked{
+            from,
+            mint: mint.to_account_info(),
+            to,
+            authority: self.owner.to_account_info()
+        };
+
+        let ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        transfer_checked(ctx, amount, mint.decimals)?;
+        Ok(())
+    }
+
+    pub fn withdraw_token(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (
+                self.vault_x.to_account_info(),
+                self.user_x.to_account_info(),
+                &self.mint_x,
+                self.token_program_x.to_account_info(),
+            ),
+            false => (
+                self.vault_y.to_account_info(),
+                self.user_y.to_account_info(),
+                &self.mint_y,
+                self.token_program_y.to_account_info(),
+            ),
+        };
+
+        let cpi_accounts = TransferChecked{
+            from,
+            mint: mint.to_account_info(),
+            to,
+            authority: self.pool.to_account_info()
+        };
+
+        let seeds = &[
+            &b"concentrated"[..],
+            &self.pool.seed.to_le_bytes(),
+            &[self.pool.bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, mint.decimals)?;
+        Ok(())
+    }
+
+    fn emit_liquidity_updated(&self, liquidity_delta: i128, amount_x: u64, amount_y: u64) {
+        emit!(PositionLiquidityUpdated {
+            pool: self.pool.key(),
+            position: self.position.key(),
+            owner: self.owner.key(),
+            liquidity_delta,
+            amount_x,
+            amount_y,
+            position_liquidity: self.position.liquidity,
+            pool_liquidity: self.pool.liquidity,
+        });
+    }
+}
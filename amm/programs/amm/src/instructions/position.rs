@@ -0,0 +1,116 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    events::{PositionClosed, PositionOpened},
+    state::{ConcentratedPool, Position},
+};
+
+
+#[derive(Accounts)]
+#[instruction(tick_lower: i32, tick_upper: i32)]
+pub struct OpenPosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"concentrated", pool.seed.to_le_bytes().as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, ConcentratedPool>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [
+            b"position",
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            tick_lower.to_le_bytes().as_ref(),
+            tick_upper.to_le_bytes().as_ref(),
+        ],
+        bump,
+        space = 8 + Position::INIT_SPACE
+    )]
+    pub position: Account<'info, Position>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> OpenPosition<'info> {
+    pub fn open_position(&mut self, tick_lower: i32, tick_upper: i32, bumps: &OpenPositionBumps) -> Result<()> {
+        self.pool.validate_range(tick_lower, tick_upper)?;
+
+        self.position.set_inner(Position {
+            pool: self.pool.key(),
+            owner: self.owner.key(),
+            tick_lower,
+            tick_upper,
+            liquidity: 0,
+            fee_growth_inside_x_last: 0,
+            fee_growth_inside_y_last: 0,
+            tokens_owed_x: 0,
+            tokens_owed_y: 0,
+            bump: bumps.position,
+        });
+
+        emit!(PositionOpened {
+            pool: self.pool.key(),
+            position: self.position.key(),
+            owner: self.owner.key(),
+            tick_lower,
+            tick_upper,
+        });
+
+        Ok(())
+    }
+}
+
+#[derive(Accounts)]
+pub struct ClosePosition<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"concentrated", pool.seed.to_le_bytes().as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, ConcentratedPool>,
+
+    #[account(
+        mut,
+        close = owner,
+        has_one = pool,
+        has_one = owner,
+        seeds = [
+            b"position",
+            pool.key().as_ref(),
+            owner.key().as_ref(),
+            position.tick_lower.to_le_bytes().as_ref(),
+            position.tick_upper.to_le_bytes().as_ref(),
+        ],
+        bump = position.bump,
+    )]
+    pub position: Account<'info, Position>,
+}
+
+impl<'info> ClosePosition<'info> {
+    /// Returns the position's rent once its liquidity has been withdrawn and
+    /// its fees collected.
+    pub fn close_position(&mut self) -> Result<()> {
+        require!(
+            self.position.liquidity == 0
+                && self.position.tokens_owed_x == 0
+                && self.position.tokens_owed_y == 0,
+            AmmError::PositionNotEmpty
+        );
+
+        emit!(PositionClosed {
+            pool: self.pool.key(),
+            position: self.position.key(),
+            owner: self.owner.key(),
+        });
+
+        Ok(())
+    }
+}
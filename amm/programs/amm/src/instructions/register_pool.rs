@@ -0,0 +1,90 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::AmmError, events::PoolRegistered, state::{Config, PoolRegistry}};
+
+
+#[derive(Accounts)]
+pub struct RegisterPool<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            b"registry",
+            PoolRegistry::sorted(&config.mint_x, &config.mint_y).0.as_ref(),
+            PoolRegistry::sorted(&config.mint_x, &config.mint_y).1.as_ref(),
+            config.fees.to_le_bytes().as_ref(),
+            &[config.curve as u8],
+        ],
+        bump,
+        space = 8 + PoolRegistry::INIT_SPACE
+    )]
+    pub registry: Account<'info, PoolRegistry>,
+
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> RegisterPool<'info> {
+    /// Records `config` as the canonical pool for its pair, curve and current
+    /// fee tier. The first pool registered for a tier keeps it; the entry
+    /// does not follow later `update_fees` calls.
+    ///
+    /// An entry is claimed either by `initialize`, at the initializer's
+    /// discretion, or later through this instruction. Here registration is
+    /// permissionless and entries can't be replaced, so only pools that
+    /// nobody can restrict later are accepted: the authority must be
+    /// renounced and the pool left unlocked, uncapped, without an LP
+    /// allowlist and on its static fee. A pool registered at `initialize`
+    /// is only as trustworthy as its authority, which routers should check.
+    pub fn register_pool(&mut self, bumps: &RegisterPoolBumps) -> Result<()> {
+        let config = &self.config;
+        require!(
+            config.authority.is_none()
+                && !config.locked
+                && config.lp_allowlist.is_none()
+                && config.dynamic_fee.is_none()
+                && config.max_reserve_x == Config::UNCAPPED
+                && config.max_reserve_y == Config::UNCAPPED,
+            AmmError::PoolNotRegistrable
+        );
+
+        record_pool(&mut self.registry, &self.config, bumps.registry)
+    }
+}
+
+/// Fills a freshly created registry entry with `config`.
+pub fn record_pool<'info>(
+    registry: &mut Account<'info, PoolRegistry>,
+    config: &Account<'info, Config>,
+    bump: u8,
+) -> Result<()> {
+    let (mint_a, mint_b) = PoolRegistry::sorted(&config.mint_x, &config.mint_y);
+
+    registry.set_inner(PoolRegistry {
+        config: config.key(),
+        mint_a: *mint_a,
+        mint_b: *mint_b,
+        fees: config.fees,
+        curve: config.curve,
+        bump,
+    });
+
+    emit!(PoolRegistered {
+        config: config.key(),
+        registry: registry.key(),
+        mint_a: *mint_a,
+        mint_b: *mint_b,
+        fees: config.fees,
+        curve: config.curve,
+    });
+
+    Ok(())
+}
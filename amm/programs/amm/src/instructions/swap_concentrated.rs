@@ -0,0 +1,187 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked}
+};
+
+use crate::{
+    error::AmmError,
+    events::ConcentratedSwapped,
+    state::ConcentratedPool,
+    utils::{amount_after_transfer_fee, amount_with_transfer_fee},
+};
+
+
+#[derive(Accounts)]
+pub struct SwapConcentrated<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program_x
+    )]
+    pub mint_x: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program_y
+    )]
+    pub mint_y: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = pool,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_x,
+    )]
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_y,
+    )]
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [b"concentrated", pool.seed.to_le_bytes().as_ref()],
+        bump = pool.bump,
+    )]
+    pub pool: Account<'info, ConcentratedPool>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program_x: Interface<'info, TokenInterface>,
+    pub token_program_y: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> SwapConcentrated<'info> {
+    pub fn swap_concentrated(&mut self, is_x: bool, amount: u64, min_out: u64) -> Result<()> {
+        require!(amount != 0, AmmError::ZeroAmount);
+
+        let (mint_in, mint_out) = match is_x {
+            true => (&self.mint_x, &self.mint_y),
+            false => (&self.mint_y, &self.mint_x),
+        };
+
+        // Price what the vault actually receives, net of any Token-2022
+        // transfer fee on the input mint.
+        let amount_in = amount_after_transfer_fee(mint_in, amount)?;
+        require!(amount_in != 0, AmmError::ZeroAmount);
+
+        let res = self.pool.swap(is_x, amount_in)?;
+        require!(res.withdraw != 0, AmmError::ZeroAmount);
+
+        // The slippage bound applies to what the user receives.
+        let amount_out = amount_after_transfer_fee(mint_out, res.withdraw)?;
+        require!(amount_out >= min_out, AmmError::SlippageExceeded);
+
+        // A swap that ran out of liquidity only pulls what it used.
+        let deposit = match res.deposit == amount_in {
+            true => amount,
+            false => amount_with_transfer_fee(mint_in, res.deposit)?,
+        };
+
+        self.deposit_token(is_x, deposit)?;
+        self.withdraw_token(!is_x, res.withdraw)?;
+
+        emit!(ConcentratedSwapped {
+            pool: self.pool.key(),
+            user: self.user.key(),
+            is_x,
+            amount_in: deposit,
+            amount_out,
+            fee: res.fee,
+            sqrt_price: self.pool.sqrt_price,
+            tick: self.pool.tick,
+            liquidity: self.pool.liquidity,
+        });
+
+        Ok(())
+    }
+
+    pub fn deposit_token(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (
+                self.user_x.to_account_info(),
+                self.vault_x.to_account_info(),
+                &self.mint_x,
+                self.token_program_x.to_account_info(),
+            ),
+            false => (
+                self.user_y.to_account_info(),
+                self.vault_y.to_account_info(),
+                &self.mint_y,
+                self.token_program_y.to_account_info(),
+            ),
+        };
+
+        let cpi_accounts = TransferChecked{
+            from,
+            mint: mint.to_account_info(),
+            to,
+            authority: self.user.to_account_info()
+        };
+
+        let ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        transfer_checked(ctx, amount, mint.decimals)?;
+        Ok(())
+    }
+
+    pub fn withdraw_token(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (
+                self.vault_x.to_account_info(),
+                self.user_x.to_account_info(),
+                &self.mint_x,
+                self.token_program_x.to_account_info(),
+            ),
+            false => (
+                self.vault_y.to_account_info(),
+                self.user_y.to_account_info(),
+                &self.mint_y,
+                self.token_program_y.to_account_info(),
+            ),
+        };
+
+        let cpi_accounts = TransferChecked{
+            from,
+            mint: mint.to_account_info(),
+            to,
+            authority: self.pool.to_account_info()
+        };
+
+        let seeds = &[
+            &b"concentrated"[..],
+            &self.pool.seed.to_le_bytes(),
+            &[self.pool.bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, mint.decimals)?;
+        Ok(())
+    }
+}
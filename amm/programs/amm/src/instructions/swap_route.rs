@@ -0,0 +1,228 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked}
+};
+
+use crate::{error::AmmError, events::RouteHopSwapped, state::Config, utils::amount_after_transfer_fee};
+
+
+/// Accounts for a multi-hop swap. Each hop is passed through
+/// `remaining_accounts` as `Hop::ACCOUNTS` consecutive accounts:
+///
+/// `config, mint_lp, mint_in, mint_out, vault_in, vault_out, user_in, user_out,
+/// token_program_in, token_program_out`
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+}
+
+impl<'info> SwapRoute<'info> {
+    // `usize::is_multiple_of` is newer than the platform-tools rustc.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn swap_route(&mut self, remaining_accounts: &'info [AccountInfo<'info>], amount: u64, min_out: u64) -> Result<()> {
+        require!(amount != 0, AmmError::ZeroAmount);
+        require!(
+            !remaining_accounts.is_empty() && remaining_accounts.len() % Hop::ACCOUNTS == 0,
+            AmmError::InvalidRoute
+        );
+        require!(remaining_accounts.len() / Hop::ACCOUNTS <= Hop::MAX_HOPS, AmmError::InvalidRoute);
+
+        let mut amount_in = amount;
+        let mut previous_mint: Option<Pubkey> = None;
+
+        for (index, accounts) in remaining_accounts.chunks(Hop::ACCOUNTS).enumerate() {
+            // Hops are loaded one at a time so that a pool visited twice sees
+            // the balances and config left by the previous hop.
+            let mut hop = Hop::load(accounts, &self.user.key())?;
+
+            if let Some(mint) = previous_mint {
+                require_keys_eq!(mint, hop.mint_in.key(), AmmError::InvalidRoute);
+            }
+
+            let amount_out = hop.swap(&self.user, index as u8, amount_in)?;
+
+            previous_mint = Some(hop.mint_out.key());
+            amount_in = amount_out;
+        }
+
+        // Only the final output is bounded; intermediate hops take whatever
+        // the pools give.
+        require!(amount_in >= min_out, AmmError::SlippageExceeded);
+
+        Ok(())
+    }
+}
+
+pub struct Hop<'info> {
+    pub config: Account<'info, Config>,
+    pub mint_lp: InterfaceAccount<'info, Mint>,
+    pub mint_in: InterfaceAccount<'info, Mint>,
+    pub mint_out: InterfaceAccount<'info, Mint>,
+    pub vault_in: InterfaceAccount<'info, TokenAccount>,
+    pub vault_out: InterfaceAccount<'info, TokenAccount>,
+    pub user_in: InterfaceAccount<'info, TokenAccount>,
+    pub user_out: InterfaceAccount<'info, TokenAccount>,
+    pub token_program_in: Interface<'info, TokenInterface>,
+    pub token_program_out: Interface<'info, TokenInterface>,
+}
+
+impl<'info> Hop<'info> {
+    pub const ACCOUNTS: usize = 10;
+    pub const MAX_HOPS: usize = 4;
+
+    /// Deserializes and validates one hop, performing the same checks the
+    /// `Swap` account constraints do.
+    pub fn load(accounts: &'info [AccountInfo<'info>], user: &Pubkey) -> Result<Self> {
+        require!(accounts[0].is_writable, AmmError::InvalidRoute);
+
+        let hop = Hop {
+            config: Account::try_from(&accounts[0])?,
+            mint_lp: InterfaceAccount::try_from(&accounts[1])?,
+            mint_in: InterfaceAccount::try_from(&accounts[2])?,
+            mint_out: InterfaceAccount::try_from(&accounts[3])?,
+            vault_in: InterfaceAccount::try_from(&accounts[4])?,
+            vault_out: InterfaceAccount::try_from(&accounts[5])?,
+            user_in: InterfaceAccount::try_from(&accounts[6])?,
+            user_out: InterfaceAccount::try_from(&accounts[7])?,
+            token_program_in: Interface::try_from(&accounts[8])?,
+            token_program_out: Interface::try_from(&accounts[9])?,
+        };
+
+        let config = &hop.config;
+        let config_key = config.key();
+
+        let config_address = Pubkey::create_program_address(
+            &[b"config", config.seed.to_le_bytes().as_ref(), &[config.config_bump]],
+            &crate::ID,
+        ).map_err(|_| AmmError::InvalidRoute)?;
+        require_keys_eq!(config_address, config_key, AmmError::InvalidRoute);
+
+        let lp_address = Pubkey::create_program_address(
+            &[b"lp", config_key.as_ref(), &[config.lp_bump]],
+            &crate::ID,
+        ).map_err(|_| AmmError::InvalidRoute)?;
+        require_keys_eq!(lp_address, hop.mint_lp.key(), AmmError::InvalidRoute);
+
+        let (mint_in, mint_out) = (hop.mint_in.key(), hop.mint_out.key());
+        require!(
+            (mint_in == config.mint_x && mint_out == config.mint_y)
+                || (mint_in == config.mint_y && mint_out == config.mint_x),
+            AmmError::InvalidRoute
+        );
+
+        require_keys_eq!(*hop.mint_in.to_account_info().owner, hop.token_program_in.key(), AmmError::InvalidRoute);
+        require_keys_eq!(*hop.mint_out.to_account_info().owner, hop.token_program_out.key(), AmmError::InvalidRoute);
+
+        require_keys_eq!(
+            hop.vault_in.key(),
+            get_associated_token_address_with_program_id(&config_key, &mint_in, &hop.token_program_in.key()),
+            AmmError::InvalidVault
+        );
+        require_keys_eq!(
+            hop.vault_out.key(),
+            get_associated_token_address_with_program_id(&config_key, &mint_out, &hop.token_program_out.key()),
+            AmmError::InvalidVault
+        );
+
+        require_keys_eq!(hop.user_in.owner, *user, AmmError::InvalidRoute);
+        require_keys_eq!(hop.user_in.mint, mint_in, AmmError::InvalidRoute);
+        require_keys_eq!(hop.user_out.owner, *user, AmmError::InvalidRoute);
+        require_keys_eq!(hop.user_out.mint, mint_out, AmmError::InvalidRoute);
+
+        Ok(hop)
+    }
+
+    /// Swaps `amount` of `mint_in` through the pool as hop number `index` and
+    /// returns what the user received of `mint_out`, net of transfer fees.
+    pub fn swap(&mut self, user: &Signer<'info>, index: u8, amount: u64) -> Result<u64> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(self.config.flash_loan_owed == 0, AmmError::FlashLoanActive);
+
+        let is_x = self.mint_in.key() == self.config.mint_x;
+
+        let (vault_x, vault_y) = match is_x {
+            true => (self.vault_in.amount, self.vault_out.amount),
+            false => (self.vault_out.amount, self.vault_in.amount),
+        };
+        let (reserve_x, reserve_y) = self.config.reserves(vault_x, vault_y)?;
+        self.config.update_oracle(reserve_x, reserve_y)?;
+
+        let amount_in = amount_after_transfer_fee(&self.mint_in, amount)?;
+        require!(amount_in != 0, AmmError::ZeroAmount);
+
+        let now = Clock::get()?.unix_timestamp;
+        let pool = self.config.pool(vault_x, vault_y, self.mint_lp.supply, now)?;
+        let res = pool.swap(is_x, amount_in).map_err(AmmError::from)?;
+
+        // The input side keeps the deposit less the protocol's share.
+        let kept = res.deposit - res.protocol_fee;
+        let after = match is_x {
+            true => (reserve_x.checked_add(kept).ok_or(AmmError::Overflow)?, reserve_y - res.withdraw),
+            false => (reserve_x - res.withdraw, reserve_y.checked_add(kept).ok_or(AmmError::Overflow)?),
+        };
+
+        self.config.accrue_protocol_fee(is_x, res.protocol_fee)?;
+        self.config.update_volatility((reserve_x, reserve_y), after, now)?;
+        self.config.exit(&crate::ID)?;
+
+        self.deposit_token(user, amount)?;
+        self.withdraw_token(res.withdraw)?;
+
+        let amount_out = amount_after_transfer_fee(&self.mint_out, res.withdraw)?;
+
+        emit!(RouteHopSwapped {
+            config: self.config.key(),
+            hop: index,
+            mint_in: self.mint_in.key(),
+            mint_out: self.mint_out.key(),
+            amount_in: amount,
+            amount_out,
+            fees: pool.fees,
+            reserve_x_before: reserve_x,
+            reserve_y_before: reserve_y,
+            reserve_x_after: after.0,
+            reserve_y_after: after.1,
+            lp_supply: self.mint_lp.supply,
+        });
+
+        Ok(amount_out)
+    }
+
+    fn deposit_token(&self, user: &Signer<'info>, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked{
+            from: self.user_in.to_account_info(),
+            mint: self.mint_in.to_account_info(),
+            to: self.vault_in.to_account_info(),
+            authority: user.to_account_info()
+        };
+
+        let ctx = CpiContext::new(self.token_program_in.to_account_info(), cpi_accounts);
+
+        transfer_checked(ctx, amount, self.mint_in.decimals)?;
+        Ok(())
+    }
+
+    fn withdraw_token(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = TransferChecked{
+            from: self.vault_out.to_account_info(),
+            mint: self.mint_out.to_account_info(),
+            to: self.user_out.to_account_info(),
+            authority: self.config.to_account_info()
+        };
+
+        let seeds = &[
+            &b"config"[..],
+            &self.config.seed.to_le_bytes(),
+            &[self.config.config_bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(self.token_program_out.to_account_info(), cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, self.mint_out.decimals)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use amm_quote::{dynamic_fee::DynamicFee, stable_swap};
+use anchor_lang::prelude::*;
+
+use crate::{
+    error::AmmError,
+    events::{AmpRampUpdated, AuthorityUpdated, DepositCapsUpdated, DynamicFeeUpdated, FeesUpdated, LpAllowlistUpdated, PoolLocked},
+    state::{Config, CurveType, DynamicFeeParams},
+};
+
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = config.authority == Some(authority.key()) @ AmmError::Unauthorized,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+}
+
+impl<'info> UpdateConfig<'info> {
+    pub fn lock(&mut self) -> Result<()> {
+        self.config.locked = true;
+
+        emit!(PoolLocked {
+            config: self.config.key(),
+            locked: true,
+        });
+
+        Ok(())
+    }
+
+    pub fn unlock(&mut self) -> Result<()> {
+        self.config.locked = false;
+
+        emit!(PoolLocked {
+            config: self.config.key(),
+            locked: false,
+        });
+
+        Ok(())
+    }
+
+    pub fn update_fees(&mut self, fees: u16) -> Result<()> {
+        require!(fees <= Config::MAX_FEES, AmmError::InvalidFee);
+        self.config.fees = fees;
+
+        self.emit_fees_updated();
+
+        Ok(())
+    }
+
+    pub fn update_protocol_fee(&mut self, protocol_fee: u16) -> Result<()> {
+        require!(protocol_fee <= Config::MAX_FEES, AmmError::InvalidFee);
+        self.config.protocol_fee = protocol_fee;
+
+        self.emit_fees_updated();
+
+        Ok(())
+    }
+
+    /// Starts moving the StableSwap amplification from its current value to
+    /// `amp_target`, reaching it at `ramp_end`.
+    pub fn ramp_amp(&mut self, amp_target: u64, ramp_end: i64) -> Result<()> {
+        require!(self.config.curve == CurveType::StableSwap, AmmError::NotStableSwap);
+        require!((stable_swap::MIN_AMP..=stable_swap::MAX_AMP).contains(&amp_target), AmmError::InvalidAmp);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(now >= self.config.amp_ramp_end, AmmError::InvalidAmpRamp);
+        require!(ramp_end >= now + Config::MIN_AMP_RAMP_DURATION, AmmError::InvalidAmpRamp);
+
+        let amp = self.config.amp(now);
+        require!(
+            amp_target <= amp * Config::MAX_AMP_CHANGE && amp <= amp_target * Config::MAX_AMP_CHANGE,
+            AmmError::InvalidAmpRamp
+        );
+
+        self.config.amp_initial = amp;
+        self.config.amp_target = amp_target;
+        self.config.amp_ramp_start = now;
+        self.config.amp_ramp_end = ramp_end;
+
+        self.emit_amp_ramp_updated();
+
+        Ok(())
+    }
+
+    /// Freezes the amplification at its current value.
+    pub fn stop_ramp_amp(&mut self) -> Result<()> {
+        require!(self.config.curve == CurveType::StableSwap, AmmError::NotStableSwap);
+
+        let now = Clock::get()?.unix_timestamp;
+        let amp = self.config.amp(now);
+
+        self.config.amp_initial = amp;
+        self.config.amp_target = amp;
+        self.config.amp_ramp_start = now;
+        self.config.amp_ramp_end = now;
+
+        self.emit_amp_ramp_updated();
+
+        Ok(())
+    }
+
+    /// Caps the reserves deposits may build up to. `Config::UNCAPPED` lifts
+    /// the cap on a side; lowering a cap below the current reserves only
+    /// blocks further deposits.
+    pub fn set_deposit_caps(&mut self, max_reserve_x: u64, max_reserve_y: u64) -> Result<()> {
+        self.config.max_reserve_x = max_reserve_x;
+        self.config.max_reserve_y = max_reserve_y;
+
+        emit!(DepositCapsUpdated {
+            config: self.config.key(),
+            max_reserve_x,
+            max_reserve_y,
+        });
+
+        Ok(())
+    }
+
+    /// Restricts deposits to wallets in the merkle tree with `root`; see
+    /// `utils::allowlist_leaf` for how the tree is built.
+    pub fn set_lp_allowlist(&mut self, root: [u8; 32]) -> Result<()> {
+        self.config.lp_allowlist = Some(root);
+
+        self.emit_lp_allowlist_updated();
+
+        Ok(())
+    }
+
+    pub fn remove_lp_allowlist(&mut self) -> Result<()> {
+        self.config.lp_allowlist = None;
+
+        self.emit_lp_allowlist_updated();
+
+        Ok(())
+    }
+
+    /// Switches swaps to a volatility-scaled fee between `min_fees` and
+    /// `max_fees`. Updating the bounds of an active mode keeps the
+    /// accumulated volatility; enabling it starts from zero.
+    pub fn set_dynamic_fee(&mut self, params: DynamicFeeParams) -> Result<()> {
+        DynamicFee::from(params).validate().map_err(AmmError::from)?;
+
+        if self.config.dynamic_fee.is_none() {
+            self.config.volatility = 0;
+            self.config.volatility_updated_at = Clock::get()?.unix_timestamp;
+        }
+        self.config.dynamic_fee = Some(params);
+
+        self.emit_dynamic_fee_updated();
+
+        Ok(())
+    }
+
+    /// Returns swaps to the static `fees`.
+    pub fn disable_dynamic_fee(&mut self) -> Result<()> {
+        self.config.dynamic_fee = None;
+
+        self.emit_dynamic_fee_updated();
+
+        Ok(())
+    }
+
+    pub fn transfer_authority(&mut self, new_authority: Pubkey) -> Result<()> {
+        self.config.authority = Some(new_authority);
+
+        self.emit_authority_updated();
+
+        Ok(())
+    }
+
+    /// Leaves the pool without an authority for good. Nobody could collect
+    /// protocol fees afterwards, so any owed must be collected first and the
+    /// protocol cut drops to zero, leaving the whole swap fee to LPs.
+    pub fn renounce_authority(&mut self) -> Result<()> {
+        require!(
+            self.config.protocol_fees_x == 0 && self.config.protocol_fees_y == 0,
+            AmmError::ProtocolFeesUncollected
+        );
+
+        self.config.authority = None;
+        self.config.protocol_fee = 0;
+
+        self.emit_fees_updated();
+        self.emit_authority_updated();
+
+        Ok(())
+    }
+
+    fn emit_fees_updated(&self) {
+        emit!(FeesUpdated {
+            config: self.config.key(),
+            fees: self.config.fees,
+            protocol_fee: self.config.protocol_fee,
+        });
+    }
+
+    fn emit_amp_ramp_updated(&self) {
+        emit!(AmpRampUpdated {
+            config: self.config.key(),
+            amp_initial: self.config.amp_initial,
+            amp_target: self.config.amp_target,
+            amp_ramp_start: self.config.amp_ramp_start,
+            amp_ramp_end: self.config.amp_ramp_end,
+        });
+    }
+
+    fn emit_lp_allowlist_updated(&self) {
+        emit!(LpAllowlistUpdated {
+            config: self.config.key(),
+            root: self.config.lp_allowlist,
+        });
+    }
+
+    fn emit_dynamic_fee_updated(&self) {
+        emit!(DynamicFeeUpdated {
+            config: self.config.key(),
+            dynamic_fee: self.config.dynamic_fee,
+        });
+    }
+
+    fn emit_authority_updated(&self) {
+        emit!(AuthorityUpdated {
+            config: self.config.key(),
+            authority: self.config.authority,
+        });
+    }
+}
@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface, TransferChecked, transfer_checked, Burn, burn}
+};
+
+use crate::{error::AmmError, events::LiquidityRemoved, state::Config, utils::amount_after_transfer_fee};
+
+
+#[derive(Accounts)]
+pub struct Withdraw<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mint::token_program = token_program_x
+    )]
+    pub mint_x: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mint::token_program = token_program_y
+    )]
+    pub mint_y: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        seeds = [b"lp", config.key().as_ref()],
+        bump = config.lp_bump,
+        mint::token_program = token_program_lp,
+    )]
+    pub mint_lp: InterfaceAccount<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_x,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_x,
+    )]
+    pub vault_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_y,
+        associated_token::authority = config,
+        associated_token::token_program = token_program_y,
+    )]
+    pub vault_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_x,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_x,
+    )]
+    pub user_x: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint_y,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_y,
+    )]
+    pub user_y: InterfaceAccount<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        has_one = mint_x,
+        has_one = mint_y,
+        seeds = [b"config", config.seed.to_le_bytes().as_ref()],
+        bump = config.config_bump,
+    )]
+    pub config: Account<'info, Config>,
+
+    #[account(
+        mut,
+        associated_token::mint = mint_lp,
+        associated_token::authority = user,
+        associated_token::token_program = token_program_lp,
+    )]
+    pub user_lp: InterfaceAccount<'info, TokenAccount>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program_x: Interface<'info, TokenInterface>,
+    pub token_program_y: Interface<'info, TokenInterface>,
+    pub token_program_lp: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> Withdraw<'info> {
+    pub fn withdraw(&mut self, amount: u64, min_x: u64, min_y: u64) -> Result<()> {
+        require!(!self.config.locked, AmmError::PoolLocked);
+        require!(self.config.flash_loan_owed == 0, AmmError::FlashLoanActive);
+        require!(amount != 0, AmmError::ZeroAmount);
+        require!(self.user_lp.amount >= amount, AmmError::InsufficientBalance);
+
+        let pool = self.config.pool(
+            self.vault_x.amount,
+            self.vault_y.amount,
+            self.mint_lp.supply,
+            Clock::get()?.unix_timestamp,
+        )?;
+        let (reserve_x, reserve_y) = (pool.reserve_x, pool.reserve_y);
+        self.config.update_oracle(reserve_x, reserve_y)?;
+
+        let amounts = pool.withdraw(amount).map_err(AmmError::from)?;
+
+        // The slippage bounds apply to what the user receives, net of any
+        // Token-2022 transfer fees.
+        let x = amount_after_transfer_fee(&self.mint_x, amounts.x)?;
+        let y = amount_after_transfer_fee(&self.mint_y, amounts.y)?;
+        require!(x >= min_x && y >= min_y, AmmError::SlippageExceeded);
+
+        self.burn_lp_token(amount)?;
+        self.withdraw_token(true, amounts.x)?;
+        self.withdraw_token(false, amounts.y)?;
+
+        let lp_supply_before = self.mint_lp.supply;
+        self.vault_x.reload()?;
+        self.vault_y.reload()?;
+        self.mint_lp.reload()?;
+        let (reserve_x_after, reserve_y_after) = self.config.reserves(self.vault_x.amount, self.vault_y.amount)?;
+
+        emit!(LiquidityRemoved {
+            config: self.config.key(),
+            user: self.user.key(),
+            lp_amount: amount,
+            reserve_x_before: reserve_x,
+            reserve_y_before: reserve_y,
+            lp_supply_before,
+            reserve_x_after,
+            reserve_y_after,
+            lp_supply_after: self.mint_lp.supply,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw_token(&self, is_x: bool, amount: u64) -> Result<()> {
+        let (from, to, mint, cpi_program) = match is_x {
+            true => (
+                self.vault_x.to_account_info(),
+                self.user_x.to_account_info(),
+                &self.mint_x,
+                self.token_program_x.to_account_info(),
+            ),
+            false => (
+                self.vault_y.to_account_info(),
+                self.user_y.to_account_info(),
+                &self.mint_y,
+                self.token_program_y.to_account_info(),
+            ),
+        };
+
+        let cpi_accounts = TransferChecked{
+            from,
+            mint: mint.to_account_info(),
+            to,
+            authority: self.config.to_account_info()
+        };
+
+        let seeds = &[
+            &b"config"[..],
+            &self.config.seed.to_le_bytes(),
+            &[self.config.config_bump],
+        ];
+
+        let signer_seeds = &[&seeds[..]];
+
+        let ctx = CpiContext::new_with_signer(cpi_program, cpi_accounts, signer_seeds);
+
+        transfer_checked(ctx, amount, mint.decimals)?;
+        Ok(())
+    }
+
+    pub fn burn_lp_token(&self, amount: u64) -> Result<()> {
+        let cpi_program = self.token_program_lp.to_account_info();
+
+        let cpi_accounts = Burn{
+            mint: self.mint_lp.to_account_info(),
+            from: self.user_lp.to_account_info(),
+            authority: self.user.to_account_info(),
+        };
+
+        let ctx = CpiContext::new(cpi_program, cpi_accounts);
+
+        burn(ctx, amount)?;
+        Ok(())
+    }
+}
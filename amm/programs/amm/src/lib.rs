@@ -3,10 +3,14 @@ use anchor_lang::prelude::*;
 
 declare_id!("BPWKPL7uX4zWkm5FcJgbzFPaHJrck8vjfAkNhRwx6DA2");
 
+pub mod error;
+pub mod events;
 pub mod instructions;
 pub mod state;
+pub mod utils;
 
 use instructions::*;
+use state::{CurveType, DynamicFeeParams};
 
 #[program]
 pub mod amm {
@@ -16,9 +20,18 @@ pub mod amm {
         ctx: Context<Initialize>,
         seed: u64,
         fees: u16,
+        lp_decimals: u8,
         authority: Option<Pubkey>,
+        curve: CurveType,
+        amp: u64,
     ) -> Result<()> {
-        ctx.accounts.initialize(seed, fees, authority, &ctx.bumps)?;
+        ctx.accounts.initialize(seed, fees, lp_decimals, authority, curve, amp, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn register_pool(ctx: Context<RegisterPool>) -> Result<()> {
+        ctx.accounts.register_pool(&ctx.bumps)?;
 
         Ok(())
     }
@@ -28,8 +41,217 @@ pub mod amm {
         amount: u64,
         max_x: u64,
         max_y: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.deposit(amount, max_x, max_y, &proof)?;
+
+        Ok(())
+    }
+
+    pub fn deposit_single(
+        ctx: Context<Deposit>,
+        is_x: bool,
+        amount: u64,
+        min_lp_out: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        ctx.accounts.deposit_single(is_x, amount, min_lp_out, &proof)?;
+
+        Ok(())
+    }
+
+    pub fn swap(
+        ctx: Context<Swap>,
+        is_x: bool,
+        amount: u64,
+        min_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.swap(is_x, amount, min_out)?;
+
+        Ok(())
+    }
+
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        amount: u64,
+        min_out: u64,
+    ) -> Result<()> {
+        ctx.accounts.swap_route(ctx.remaining_accounts, amount, min_out)?;
+
+        Ok(())
+    }
+
+    pub fn withdraw(
+        ctx: Context<Withdraw>,
+        amount: u64,
+        min_x: u64,
+        min_y: u64,
+    ) -> Result<()> {
+        ctx.accounts.withdraw(amount, min_x, min_y)?;
+
+        Ok(())
+    }
+
+    pub fn flash_borrow(ctx: Context<FlashLoan>, amount: u64) -> Result<()> {
+        ctx.accounts.flash_borrow(amount)?;
+
+        Ok(())
+    }
+
+    pub fn flash_repay(ctx: Context<FlashLoan>) -> Result<()> {
+        ctx.accounts.flash_repay()?;
+
+        Ok(())
+    }
+
+    pub fn lock(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.lock()?;
+
+        Ok(())
+    }
+
+    pub fn unlock(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.unlock()?;
+
+        Ok(())
+    }
+
+    pub fn update_fees(ctx: Context<UpdateConfig>, fees: u16) -> Result<()> {
+        ctx.accounts.update_fees(fees)?;
+
+        Ok(())
+    }
+
+    pub fn update_protocol_fee(ctx: Context<UpdateConfig>, protocol_fee: u16) -> Result<()> {
+        ctx.accounts.update_protocol_fee(protocol_fee)?;
+
+        Ok(())
+    }
+
+    pub fn ramp_amp(ctx: Context<UpdateConfig>, amp_target: u64, ramp_end: i64) -> Result<()> {
+        ctx.accounts.ramp_amp(amp_target, ramp_end)?;
+
+        Ok(())
+    }
+
+    pub fn stop_ramp_amp(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.stop_ramp_amp()?;
+
+        Ok(())
+    }
+
+    pub fn set_deposit_caps(
+        ctx: Context<UpdateConfig>,
+        max_reserve_x: u64,
+        max_reserve_y: u64,
+    ) -> Result<()> {
+        ctx.accounts.set_deposit_caps(max_reserve_x, max_reserve_y)?;
+
+        Ok(())
+    }
+
+    pub fn set_lp_allowlist(ctx: Context<UpdateConfig>, root: [u8; 32]) -> Result<()> {
+        ctx.accounts.set_lp_allowlist(root)?;
+
+        Ok(())
+    }
+
+    pub fn remove_lp_allowlist(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.remove_lp_allowlist()?;
+
+        Ok(())
+    }
+
+    pub fn set_dynamic_fee(ctx: Context<UpdateConfig>, params: DynamicFeeParams) -> Result<()> {
+        ctx.accounts.set_dynamic_fee(params)?;
+
+        Ok(())
+    }
+
+    pub fn disable_dynamic_fee(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.disable_dynamic_fee()?;
+
+        Ok(())
+    }
+
+    pub fn transfer_authority(ctx: Context<UpdateConfig>, new_authority: Pubkey) -> Result<()> {
+        ctx.accounts.transfer_authority(new_authority)?;
+
+        Ok(())
+    }
+
+    pub fn renounce_authority(ctx: Context<UpdateConfig>) -> Result<()> {
+        ctx.accounts.renounce_authority()?;
+
+        Ok(())
+    }
+
+    pub fn collect_protocol_fees(ctx: Context<CollectProtocolFees>) -> Result<()> {
+        ctx.accounts.collect_protocol_fees()?;
+
+        Ok(())
+    }
+
+    pub fn initialize_concentrated(
+        ctx: Context<InitializeConcentrated>,
+        seed: u64,
+        fees: u16,
+        tick_spacing: u16,
+        min_position_liquidity: u128,
+        sqrt_price: u128,
+    ) -> Result<()> {
+        ctx.accounts.initialize_concentrated(seed, fees, tick_spacing, min_position_liquidity, sqrt_price, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn open_position(ctx: Context<OpenPosition>, tick_lower: i32, tick_upper: i32) -> Result<()> {
+        ctx.accounts.open_position(tick_lower, tick_upper, &ctx.bumps)?;
+
+        Ok(())
+    }
+
+    pub fn increase_liquidity(
+        ctx: Context<ModifyPosition>,
+        liquidity: u128,
+        max_x: u64,
+        max_y: u64,
+    ) -> Result<()> {
+        ctx.accounts.increase_liquidity(liquidity, max_x, max_y)?;
+
+        Ok(())
+    }
+
+    pub fn decrease_liquidity(
+        ctx: Context<ModifyPosition>,
+        liquidity: u128,
+        min_x: u64,
+        min_y: u64,
+    ) -> Result<()> {
+        ctx.accounts.decrease_liquidity(liquidity, min_x, min_y)?;
+
+        Ok(())
+    }
+
+    pub fn collect_fees(ctx: Context<ModifyPosition>) -> Result<()> {
+        ctx.accounts.collect_fees()?;
+
+        Ok(())
+    }
+
+    pub fn close_position(ctx: Context<ClosePosition>) -> Result<()> {
+        ctx.accounts.close_position()?;
+
+        Ok(())
+    }
+
+    pub fn swap_concentrated(
+        ctx: Context<SwapConcentrated>,
+        is_x: bool,
+        amount: u64,
+        min_out: u64,
     ) -> Result<()> {
-        ctx.accounts.deposit(amount, max_x, max_y)?;
+        ctx.accounts.swap_concentrated(is_x, amount, min_out)?;
 
         Ok(())
     }
@@ -0,0 +1,267 @@
+use amm_quote::{
+    concentrated::{self, MAX_SQRT_PRICE, MIN_SQRT_PRICE},
+    SwapQuote,
+};
+use anchor_lang::prelude::*;
+
+use crate::error::AmmError;
+
+use super::Position;
+
+/// Boundary of at least one position's range.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, Debug, InitSpace)]
+pub struct TickState {
+    pub index: i32,
+    /// Liquidity added when the price crosses this tick upwards.
+    pub liquidity_net: i128,
+    /// Total liquidity of positions bounded by this tick.
+    pub liquidity_gross: u128,
+    /// Fee growth on the side of this tick away from the current price.
+    pub fee_growth_outside_x: u128,
+    pub fee_growth_outside_y: u128,
+}
+
+/// Pool whose liquidity is provided over price ranges and tracked by
+/// `Position` accounts rather than an LP mint. Initialized ticks are stored
+/// inline, sorted by index, so swaps need no extra accounts at the cost of a
+/// cap of `MAX_TICKS` distinct range boundaries per pool, shared by every
+/// position. Once it is reached, new positions must reuse boundaries that are
+/// already initialized until others are emptied. `min_position_liquidity`
+/// keeps the cap from being filled cheaply with dust positions.
+#[account]
+#[derive(InitSpace)]
+pub struct ConcentratedPool {
+    pub seed: u64,
+    pub mint_x: Pubkey,
+    pub mint_y: Pubkey,
+    pub fees: u16,
+    pub tick_spacing: u16,
+    /// Smallest liquidity a position may hold other than zero.
+    pub min_position_liquidity: u128,
+    /// `sqrt(y / x)` as Q64.64.
+    pub sqrt_price: u128,
+    pub tick: i32,
+    /// Liquidity of the positions in range at the current tick.
+    pub liquidity: u128,
+    /// Fees earned per unit of liquidity since the pool opened, Q64.64.
+    pub fee_growth_global_x: u128,
+    pub fee_growth_global_y: u128,
+    #[max_len(128)]
+    pub ticks: Vec<TickState>,
+    pub bump: u8,
+}
+
+impl ConcentratedPool {
+    /// Must match the `max_len` of `ticks`. Bounded by the 10 KiB an account
+    /// can be created with from inside a program.
+    pub const MAX_TICKS: usize = 128;
+
+    pub const MAX_TICK_SPACING: u16 = 16_384;
+
+    /// Checks that `[tick_lower, tick_upper)` is a usable range for this pool.
+    pub fn validate_range(&self, tick_lower: i32, tick_upper: i32) -> Result<()> {
+        let spacing = self.tick_spacing as i32;
+
+        require!(
+            tick_lower < tick_upper
+                && tick_lower >= concentrated::MIN_TICK
+                && tick_upper <= concentrated::MAX_TICK
+                && tick_lower % spacing == 0
+                && tick_upper % spacing == 0,
+            AmmError::InvalidTick
+        );
+
+        Ok(())
+    }
+
+    /// Adds `delta` to `position`, first crediting it the fees earned since
+    /// its last update. Returns the token amounts backing `delta`, rounded up
+    /// when liquidity is added and down when it is removed.
+    pub fn modify_position(&mut self, position: &mut Position, delta: i128) -> Result<(u64, u64)> {
+        let (lower, upper) = (position.tick_lower, position.tick_upper);
+
+        // A position without liquidity references no ticks and earns nothing.
+        if position.liquidity == 0 && delta == 0 {
+            return Ok((0, 0));
+        }
+
+        if delta != 0 {
+            self.update_tick(lower, delta, false)?;
+            self.update_tick(upper, delta, true)?;
+        }
+
+        let (inside_x, inside_y) = self.fee_growth_inside(lower, upper)?;
+        let earned_x = concentrated::fees_earned(
+            inside_x.wrapping_sub(position.fee_growth_inside_x_last),
+            position.liquidity,
+        ).map_err(AmmError::from)?;
+        let earned_y = concentrated::fees_earned(
+            inside_y.wrapping_sub(position.fee_growth_inside_y_last),
+            position.liquidity,
+        ).map_err(AmmError::from)?;
+
+        position.tokens_owed_x = position.tokens_owed_x.checked_add(earned_x).ok_or(AmmError::Overflow)?;
+        position.tokens_owed_y = position.tokens_owed_y.checked_add(earned_y).ok_or(AmmError::Overflow)?;
+        position.fee_growth_inside_x_last = inside_x;
+        position.fee_growth_inside_y_last = inside_y;
+        position.liquidity = concentrated::add_delta(position.liquidity, delta).map_err(AmmError::from)?;
+        require!(
+            position.liquidity == 0 || position.liquidity >= self.min_position_liquidity,
+            AmmError::PositionTooSmall
+        );
+
+        if lower <= self.tick && self.tick < upper {
+            self.liquidity = concentrated::add_delta(self.liquidity, delta).map_err(AmmError::from)?;
+        }
+
+        self.ticks.retain(|tick| tick.liquidity_gross != 0);
+
+        Ok(concentrated::amounts_for_liquidity(
+            self.sqrt_price,
+            self.tick,
+            lower,
+            upper,
+            delta.unsigned_abs(),
+            delta > 0,
+        ).map_err(AmmError::from)?)
+    }
+
+    /// Exact-in swap of `amount`, `is_x` selecting X as the input side, crossing
+    /// initialized ticks as needed. If liquidity runs out at the end of the
+    /// price range the swap stops there and `deposit` is less than `amount`.
+    pub fn swap(&mut self, is_x: bool, amount: u64) -> Result<SwapQuote> {
+        let limit = match is_x {
+            true => MIN_SQRT_PRICE,
+            false => MAX_SQRT_PRICE,
+        };
+
+        let (mut remaining, mut withdraw, mut fee) = (amount, 0u64, 0u64);
+
+        while remaining != 0 && self.sqrt_price != limit {
+            let next = match is_x {
+                true => self.ticks.iter().rposition(|tick| tick.index <= self.tick),
+                false => self.ticks.iter().position(|tick| tick.index > self.tick),
+            };
+            let target = match next {
+                Some(i) => concentrated::sqrt_price_at_tick(self.ticks[i].index).map_err(AmmError::from)?,
+                None => limit,
+            };
+
+            let step = concentrated::compute_swap_step(is_x, self.sqrt_price, target, self.liquidity, remaining, self.fees)
+                .map_err(AmmError::from)?;
+
+            remaining = remaining
+                .checked_sub(step.amount_in + step.fee)
+                .ok_or(AmmError::Underflow)?;
+            withdraw = withdraw.checked_add(step.amount_out).ok_or(AmmError::Overflow)?;
+            fee = fee.checked_add(step.fee).ok_or(AmmError::Overflow)?;
+
+            let growth = concentrated::fee_growth(step.fee, self.liquidity);
+            match is_x {
+                true => self.fee_growth_global_x = self.fee_growth_global_x.wrapping_add(growth),
+                false => self.fee_growth_global_y = self.fee_growth_global_y.wrapping_add(growth),
+            }
+
+            self.sqrt_price = step.sqrt_price_next;
+            match next {
+                Some(i) if step.sqrt_price_next == target => self.cross(i, is_x)?,
+                _ => self.tick = concentrated::tick_at_sqrt_price(self.sqrt_price).map_err(AmmError::from)?,
+            }
+        }
+
+        Ok(SwapQuote {
+            deposit: amount - remaining,
+            withdraw,
+            fee,
+            protocol_fee: 0,
+        })
+    }
+
+    /// Fee growth per unit of liquidity earned inside `[lower, upper)`.
+    fn fee_growth_inside(&self, lower: i32, upper: i32) -> Result<(u128, u128)> {
+        let lower = self.find_tick(lower)?;
+        let upper = self.find_tick(upper)?;
+        let (global_x, global_y) = (self.fee_growth_global_x, self.fee_growth_global_y);
+
+        let (below_x, below_y) = match self.tick >= lower.index {
+            true => (lower.fee_growth_outside_x, lower.fee_growth_outside_y),
+            false => (
+                global_x.wrapping_sub(lower.fee_growth_outside_x),
+                global_y.wrapping_sub(lower.fee_growth_outside_y),
+            ),
+        };
+        let (above_x, above_y) = match self.tick < upper.index {
+            true => (upper.fee_growth_outside_x, upper.fee_growth_outside_y),
+            false => (
+                global_x.wrapping_sub(upper.fee_growth_outside_x),
+                global_y.wrapping_sub(upper.fee_growth_outside_y),
+            ),
+        };
+
+        Ok((
+            global_x.wrapping_sub(below_x).wrapping_sub(above_x),
+            global_y.wrapping_sub(below_y).wrapping_sub(above_y),
+        ))
+    }
+
+    fn find_tick(&self, index: i32) -> Result<&TickState> {
+        let i = self.ticks
+            .binary_search_by_key(&index, |tick| tick.index)
+            .map_err(|_| AmmError::InvalidTick)?;
+
+        Ok(&self.ticks[i])
+    }
+
+    /// Adds `delta` to the liquidity bounded by tick `index`, initializing the
+    /// tick on first use. Ticks left without liquidity are removed by
+    /// `modify_position` once fees have been settled.
+    fn update_tick(&mut self, index: i32, delta: i128, is_upper: bool) -> Result<()> {
+        let i = match self.ticks.binary_search_by_key(&index, |tick| tick.index) {
+            Ok(i) => i,
+            Err(i) => {
+                require!(self.ticks.len() < Self::MAX_TICKS, AmmError::TooManyTicks);
+
+                // By convention all growth so far happened below a tick at or
+                // under the current price.
+                let (outside_x, outside_y) = match index <= self.tick {
+                    true => (self.fee_growth_global_x, self.fee_growth_global_y),
+                    false => (0, 0),
+                };
+
+                self.ticks.insert(i, TickState {
+                    index,
+                    fee_growth_outside_x: outside_x,
+                    fee_growth_outside_y: outside_y,
+                    ..Default::default()
+                });
+                i
+            }
+        };
+
+        let tick = &mut self.ticks[i];
+        tick.liquidity_gross = concentrated::add_delta(tick.liquidity_gross, delta).map_err(AmmError::from)?;
+        tick.liquidity_net = match is_upper {
+            true => tick.liquidity_net.checked_sub(delta),
+            false => tick.liquidity_net.checked_add(delta),
+        }.ok_or(AmmError::Overflow)?;
+
+        Ok(())
+    }
+
+    fn cross(&mut self, i: usize, is_x: bool) -> Result<()> {
+        let tick = &mut self.ticks[i];
+        tick.fee_growth_outside_x = self.fee_growth_global_x.wrapping_sub(tick.fee_growth_outside_x);
+        tick.fee_growth_outside_y = self.fee_growth_global_y.wrapping_sub(tick.fee_growth_outside_y);
+
+        // Moving down leaves the ranges that start at this tick.
+        let (delta, tick) = match is_x {
+            true => (tick.liquidity_net.checked_neg().ok_or(AmmError::Overflow)?, tick.index - 1),
+            false => (tick.liquidity_net, tick.index),
+        };
+
+        self.liquidity = concentrated::add_delta(self.liquidity, delta).map_err(AmmError::from)?;
+        self.tick = tick;
+
+        Ok(())
+    }
+}
@@ -1,5 +1,36 @@
+use amm_quote::{dynamic_fee::{price_change_bps, spot_price_change_bps, DynamicFee}, stable_swap, Curve, Pool};
 use anchor_lang::prelude::*;
 
+use crate::{error::AmmError, utils::verify_allowlist_proof};
+
+use super::{spot_prices, Observation};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub enum CurveType {
+    ConstantProduct,
+    StableSwap,
+}
+
+/// Bounds for dynamic-fee mode; see `amm_quote::dynamic_fee`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug, InitSpace)]
+pub struct DynamicFeeParams {
+    pub min_fees: u16,
+    pub max_fees: u16,
+    pub max_volatility: u64,
+    pub decay_period: i64,
+}
+
+impl From<DynamicFeeParams> for DynamicFee {
+    fn from(params: DynamicFeeParams) -> Self {
+        DynamicFee {
+            min_fees: params.min_fees,
+            max_fees: params.max_fees,
+            max_volatility: params.max_volatility,
+            decay_period: params.decay_period,
+        }
+    }
+}
+
 #[account]
 #[derive(InitSpace)]
 pub struct Config {
@@ -8,7 +39,210 @@ pub struct Config {
     pub mint_x: Pubkey,
     pub mint_y: Pubkey,
     pub fees: u16,
+    pub curve: CurveType,
+    pub amp_initial: u64,
+    pub amp_target: u64,
+    pub amp_ramp_start: i64,
+    pub amp_ramp_end: i64,
+    pub protocol_fee: u16,
+    pub protocol_fees_x: u64,
+    pub protocol_fees_y: u64,
+    pub price_x_cumulative: u128,
+    pub price_y_cumulative: u128,
+    pub last_update: i64,
+    pub flash_loan_owed: u64,
+    pub flash_loan_is_x: bool,
+    pub max_reserve_x: u64,
+    pub max_reserve_y: u64,
+    /// Merkle root of the wallets allowed to add liquidity, if restricted.
+    pub lp_allowlist: Option<[u8; 32]>,
+    /// When set, swaps are charged a volatility-scaled fee instead of `fees`.
+    pub dynamic_fee: Option<DynamicFeeParams>,
+    /// Volatility accumulator, in basis points, as of `volatility_updated_at`.
+    pub volatility: u64,
+    pub volatility_updated_at: i64,
     pub locked: bool,
     pub config_bump: u8,
     pub lp_bump: u8,
-}
\ No newline at end of file
+}
+
+impl Config {
+    /// Fees are expressed in basis points.
+    pub const MAX_FEES: u16 = amm_quote::MAX_BPS;
+
+    /// LP tokens minted to `vault_lp` on the first deposit and never
+    /// redeemable, so the share price can't be inflated from a dust supply.
+    pub const MINIMUM_LIQUIDITY: u64 = amm_quote::MINIMUM_LIQUIDITY;
+
+    pub const MAX_LP_DECIMALS: u8 = 9;
+
+    /// Shortest allowed amplification ramp, and the largest factor by which a
+    /// single ramp may move it.
+    pub const MIN_AMP_RAMP_DURATION: i64 = 86_400;
+    pub const MAX_AMP_CHANGE: u64 = 10;
+
+    /// `max_reserve_x` / `max_reserve_y` value for a side without a cap.
+    pub const UNCAPPED: u64 = u64::MAX;
+
+    /// StableSwap amplification at `now`, linearly interpolated over the
+    /// current ramp. Meaningless for constant-product pools.
+    pub fn amp(&self, now: i64) -> u64 {
+        if now >= self.amp_ramp_end || self.amp_ramp_end <= self.amp_ramp_start {
+            return self.amp_target;
+        }
+
+        let elapsed = now.saturating_sub(self.amp_ramp_start).max(0) as u128;
+        let duration = (self.amp_ramp_end - self.amp_ramp_start) as u128;
+        let (initial, target) = (self.amp_initial as u128, self.amp_target as u128);
+
+        let amp = match target >= initial {
+            true => initial + (target - initial) * elapsed / duration,
+            false => initial - (initial - target) * elapsed / duration,
+        };
+
+        amp as u64
+    }
+
+    /// Vault balances net of protocol fees that are owed to the treasury but
+    /// not yet collected. These are the reserves the curve prices against.
+    pub fn reserves(&self, vault_x: u64, vault_y: u64) -> Result<(u64, u64)> {
+        let x = vault_x.checked_sub(self.protocol_fees_x).ok_or(AmmError::Underflow)?;
+        let y = vault_y.checked_sub(self.protocol_fees_y).ok_or(AmmError::Underflow)?;
+
+        Ok((x, y))
+    }
+
+    /// Quoting view of the pool at `now` for the given vault balances. This
+    /// is what every handler prices against, and what off-chain clients
+    /// should build from a deserialized `Config` to reproduce its results.
+    pub fn pool(&self, vault_x: u64, vault_y: u64, lp_supply: u64, now: i64) -> Result<Pool> {
+        let (reserve_x, reserve_y) = self.reserves(vault_x, vault_y)?;
+
+        let curve = match self.curve {
+            CurveType::ConstantProduct => Curve::ConstantProduct,
+            CurveType::StableSwap => Curve::StableSwap { amp: self.amp(now) },
+        };
+
+        Ok(Pool {
+            reserve_x,
+            reserve_y,
+            lp_supply,
+            fees: self.current_fees(now),
+            protocol_fee: self.protocol_fee,
+            curve,
+        })
+    }
+
+    /// Swap fee at `now`: `fees`, or in dynamic-fee mode the fee for the
+    /// volatility accumulator after decay.
+    pub fn current_fees(&self, now: i64) -> u16 {
+        match self.dynamic_fee {
+            Some(params) => {
+                let params = DynamicFee::from(params);
+                params.fee(params.decay(self.volatility, now.saturating_sub(self.volatility_updated_at)))
+            }
+            None => self.fees,
+        }
+    }
+
+    /// Adds the price move of a trade from `before` to `after` reserves to
+    /// the decayed volatility accumulator. Call once the fee for the trade
+    /// has been priced. No-op outside dynamic-fee mode.
+    pub fn update_volatility(&mut self, before: (u64, u64), after: (u64, u64), now: i64) -> Result<()> {
+        let Some(params) = self.dynamic_fee else { return Ok(()) };
+        let params = DynamicFee::from(params);
+
+        let volatility = params.decay(self.volatility, now.saturating_sub(self.volatility_updated_at));
+        let change = match self.curve {
+            CurveType::ConstantProduct => price_change_bps(before.0, before.1, after.0, after.1),
+            CurveType::StableSwap if [before.0, before.1, after.0, after.1].contains(&0) => 0,
+            CurveType::StableSwap => spot_price_change_bps(
+                self.spot_prices(before.0, before.1, now)?.0,
+                self.spot_prices(after.0, after.1, now)?.0,
+            ),
+        };
+
+        self.volatility = volatility.saturating_add(change);
+        self.volatility_updated_at = now;
+
+        Ok(())
+    }
+
+    /// Fails if either reserve is above its cap. Checked after a deposit has
+    /// landed.
+    pub fn check_deposit_caps(&self, reserve_x: u64, reserve_y: u64) -> Result<()> {
+        require!(
+            reserve_x <= self.max_reserve_x && reserve_y <= self.max_reserve_y,
+            AmmError::DepositCapExceeded
+        );
+
+        Ok(())
+    }
+
+    /// Fails if an LP allowlist is set and `proof` doesn't place `user` in it.
+    pub fn check_lp_allowlist(&self, user: &Pubkey, proof: &[[u8; 32]]) -> Result<()> {
+        if let Some(root) = self.lp_allowlist {
+            require!(verify_allowlist_proof(&root, user, proof), AmmError::NotAllowlisted);
+        }
+
+        Ok(())
+    }
+
+    pub fn accrue_protocol_fee(&mut self, is_x: bool, amount: u64) -> Result<()> {
+        let accrued = match is_x {
+            true => &mut self.protocol_fees_x,
+            false => &mut self.protocol_fees_y,
+        };
+        *accrued = accrued.checked_add(amount).ok_or(AmmError::Overflow)?;
+
+        Ok(())
+    }
+
+    /// Q64.64 spot prices `(y / x, x / y)` at the given reserves: the
+    /// reserve ratio for constant product, the marginal price of the
+    /// invariant at the current amplification for StableSwap. Both reserves
+    /// must be nonzero.
+    pub fn spot_prices(&self, reserve_x: u64, reserve_y: u64, now: i64) -> Result<(u128, u128)> {
+        match self.curve {
+            CurveType::ConstantProduct => Ok(spot_prices(reserve_x, reserve_y)),
+            CurveType::StableSwap => {
+                Ok(stable_swap::spot_prices(self.amp(now), reserve_x, reserve_y).map_err(AmmError::from)?)
+            }
+        }
+    }
+
+    /// Accumulates the pre-trade spot prices over the time since the last
+    /// update. Must be called before reserves change.
+    pub fn update_oracle(&mut self, reserve_x: u64, reserve_y: u64) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let elapsed = now.saturating_sub(self.last_update);
+
+        if elapsed > 0 && reserve_x != 0 && reserve_y != 0 {
+            let (price_x, price_y) = self.spot_prices(reserve_x, reserve_y, now)?;
+            self.price_x_cumulative = self.price_x_cumulative.wrapping_add(price_x.wrapping_mul(elapsed as u128));
+            self.price_y_cumulative = self.price_y_cumulative.wrapping_add(price_y.wrapping_mul(elapsed as u128));
+        }
+        self.last_update = now;
+
+        Ok(())
+    }
+
+    /// Current observation, extrapolating the cumulative prices from the last
+    /// update to `now` at the given reserves.
+    pub fn observe(&self, reserve_x: u64, reserve_y: u64, now: i64) -> Result<Observation> {
+        let mut observation = Observation {
+            timestamp: now,
+            price_x_cumulative: self.price_x_cumulative,
+            price_y_cumulative: self.price_y_cumulative,
+        };
+        let elapsed = now.saturating_sub(self.last_update);
+
+        if elapsed > 0 && reserve_x != 0 && reserve_y != 0 {
+            let (price_x, price_y) = self.spot_prices(reserve_x, reserve_y, now)?;
+            observation.price_x_cumulative = observation.price_x_cumulative.wrapping_add(price_x.wrapping_mul(elapsed as u128));
+            observation.price_y_cumulative = observation.price_y_cumulative.wrapping_add(price_y.wrapping_mul(elapsed as u128));
+        }
+
+        Ok(observation)
+    }
+}
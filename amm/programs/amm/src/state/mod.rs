@@ -1,3 +1,11 @@
+pub mod concentrated;
 pub mod config;
+pub mod oracle;
+pub mod position;
+pub mod registry;
 
-pub use config::*;
\ No newline at end of file
+pub use concentrated::*;
+pub use config::*;
+pub use oracle::*;
+pub use position::*;
+pub use registry::*;
\ No newline at end of file
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+/// Snapshot of a pool's cumulative prices at a point in time.
+///
+/// Prices are Q64.64 fixed point and accumulate with wrapping arithmetic, so
+/// only the difference between two observations is meaningful.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, InitSpace)]
+pub struct Observation {
+    pub timestamp: i64,
+    pub price_x_cumulative: u128,
+    pub price_y_cumulative: u128,
+}
+
+impl Observation {
+    /// Time-weighted average price of X in terms of Y between `self` and a
+    /// later observation, as Q64.64. Returns `None` if no time has elapsed.
+    pub fn twap_x(&self, later: &Observation) -> Option<u128> {
+        let elapsed = later.timestamp.checked_sub(self.timestamp).filter(|t| *t > 0)?;
+        Some(later.price_x_cumulative.wrapping_sub(self.price_x_cumulative) / elapsed as u128)
+    }
+
+    /// Time-weighted average price of Y in terms of X, see [`Observation::twap_x`].
+    pub fn twap_y(&self, later: &Observation) -> Option<u128> {
+        let elapsed = later.timestamp.checked_sub(self.timestamp).filter(|t| *t > 0)?;
+        Some(later.price_y_cumulative.wrapping_sub(self.price_y_cumulative) / elapsed as u128)
+    }
+}
+
+/// Q64.64 spot prices `(y / x, x / y)` for the given reserves of a
+/// constant-product pool. See `Config::spot_prices` for any curve.
+pub fn spot_prices(reserve_x: u64, reserve_y: u64) -> (u128, u128) {
+    let price_x = ((reserve_y as u128) << 64) / reserve_x as u128;
+    let price_y = ((reserve_x as u128) << 64) / reserve_y as u128;
+
+    (price_x, price_y)
+}
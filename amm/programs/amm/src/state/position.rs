@@ -0,0 +1,19 @@
+use anchor_lang::prelude::*;
+
+/// Liquidity one owner provides to a `ConcentratedPool` over
+/// `[tick_lower, tick_upper)`. Takes the place of LP tokens for that pool
+/// type, so positions are not fungible or transferable.
+#[account]
+#[derive(InitSpace)]
+pub struct Position {
+    pub pool: Pubkey,
+    pub owner: Pubkey,
+    pub tick_lower: i32,
+    pub tick_upper: i32,
+    pub liquidity: u128,
+    pub fee_growth_inside_x_last: u128,
+    pub fee_growth_inside_y_last: u128,
+    pub tokens_owed_x: u64,
+    pub tokens_owed_y: u64,
+    pub bump: u8,
+}
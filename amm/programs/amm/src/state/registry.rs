@@ -0,0 +1,37 @@
+use anchor_lang::prelude::*;
+
+use super::CurveType;
+
+/// Canonical pool for a pair, fee tier and curve. At most one exists per
+/// sorted `(mint_a, mint_b, fees, curve)`, so routers can derive its address
+/// instead of scanning every `Config`.
+#[account]
+#[derive(InitSpace)]
+pub struct PoolRegistry {
+    pub config: Pubkey,
+    pub mint_a: Pubkey,
+    pub mint_b: Pubkey,
+    pub fees: u16,
+    pub curve: CurveType,
+    pub bump: u8,
+}
+
+impl PoolRegistry {
+    /// Orders a pair so that `(x, y)` and `(y, x)` map to the same registry.
+    pub fn sorted<'a>(mint_x: &'a Pubkey, mint_y: &'a Pubkey) -> (&'a Pubkey, &'a Pubkey) {
+        match mint_x <= mint_y {
+            true => (mint_x, mint_y),
+            false => (mint_y, mint_x),
+        }
+    }
+
+    /// Registry address for a pair, fee tier and curve, in either mint order.
+    pub fn find_address(mint_x: &Pubkey, mint_y: &Pubkey, fees: u16, curve: CurveType) -> (Pubkey, u8) {
+        let (mint_a, mint_b) = Self::sorted(mint_x, mint_y);
+
+        Pubkey::find_program_address(
+            &[b"registry", mint_a.as_ref(), mint_b.as_ref(), fees.to_le_bytes().as_ref(), &[curve as u8]],
+            &crate::ID,
+        )
+    }
+}
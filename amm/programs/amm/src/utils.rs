@@ -0,0 +1,82 @@
+use anchor_lang::{prelude::*, solana_program::hash::hashv};
+use anchor_spl::{
+    token_2022::{
+        spl_token_2022::{
+            extension::{transfer_fee::TransferFeeConfig, BaseStateWithExtensions, StateWithExtensions},
+            state::Mint as MintState,
+        },
+        ID as TOKEN_2022_PROGRAM_ID,
+    },
+    token_interface::Mint,
+};
+
+use crate::error::AmmError;
+
+/// Token-2022 transfer fee withheld from a transfer of `amount`. Always zero
+/// for legacy SPL mints and for Token-2022 mints without the extension.
+pub fn transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != TOKEN_2022_PROGRAM_ID {
+        return Ok(0);
+    }
+
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(AmmError::Overflow.into()),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Gross amount that has to be sent so that `amount` arrives after the
+/// Token-2022 transfer fee is withheld.
+pub fn amount_with_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != TOKEN_2022_PROGRAM_ID || amount == 0 {
+        return Ok(amount);
+    }
+
+    let data = mint_info.try_borrow_data()?;
+    let state = StateWithExtensions::<MintState>::unpack(&data)?;
+
+    let fee = match state.get_extension::<TransferFeeConfig>() {
+        Ok(config) => config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(AmmError::Overflow)?,
+        Err(_) => 0,
+    };
+
+    Ok(amount.checked_add(fee).ok_or(AmmError::Overflow)?)
+}
+
+/// Amount that arrives after the Token-2022 transfer fee is withheld from a
+/// transfer of `amount`.
+pub fn amount_after_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let fee = transfer_fee(mint, amount)?;
+
+    Ok(amount.checked_sub(fee).ok_or(AmmError::Underflow)?)
+}
+
+/// Leaf of an LP allowlist merkle tree. Trees are built with sha256, leaves
+/// prefixed with `0x00` and nodes with `0x01`, and each node hashes its two
+/// children in ascending order so proofs need no left/right flags.
+pub fn allowlist_leaf(user: &Pubkey) -> [u8; 32] {
+    hashv(&[&[0], user.as_ref()]).to_bytes()
+}
+
+/// Whether `proof` connects `user`'s leaf to `root`.
+pub fn verify_allowlist_proof(root: &[u8; 32], user: &Pubkey, proof: &[[u8; 32]]) -> bool {
+    let node = proof.iter().fold(allowlist_leaf(user), |node, sibling| {
+        let (left, right) = match node <= *sibling {
+            true => (node, *sibling),
+            false => (*sibling, node),
+        };
+
+        hashv(&[&[1], &left, &right]).to_bytes()
+    });
+
+    node == *root
+}
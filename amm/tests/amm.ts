@@ -24,13 +24,16 @@ describe("amm", () => {
   let user_x: Account;
   let user_y: Account;
   let user_lp: PublicKey;
+  let vault_lp: PublicKey;
   let config: PublicKey;
   let bump: number;
 
   let seed = new BN(randomBytes(8));
   
   const fees = 100;
-  const amount = new BN(50_000_000);
+  const lpDecimals = 6;
+  // first deposit mints sqrt(x * y) minus the locked minimum liquidity
+  const amount = new BN(49_000_000);
   const max_x = new BN(50_000_000);
   const max_y = new BN(50_000_000);
 
@@ -113,6 +116,13 @@ describe("amm", () => {
     );
     console.log(`User LP: ${user_lp}`);
 
+    vault_lp = await getAssociatedTokenAddress(
+      mint_lp,
+      config,
+      true,
+    );
+    console.log(`Vault LP: ${vault_lp}`);
+
     const initialValueX = await connection.getTokenAccountBalance(user_x.address);
     const initialValueY = await connection.getTokenAccountBalance(user_y.address);
     console.log(`Initial Vaule of X: ${initialValueX} | Initial Vaule of Y: ${initialValueY}`);
@@ -123,7 +133,7 @@ describe("amm", () => {
   it("Is initialized!", async () => {
     // Add your test here.
     const tx = await program.methods
-      .initialize(seed, fees,initializer.publicKey, )
+      .initialize(seed, fees, lpDecimals, initializer.publicKey, { constantProduct: {} }, new BN(0))
       .accountsStrict({
         initializer: initializer.publicKey,
         mintX: mint_x,
@@ -131,9 +141,12 @@ describe("amm", () => {
         mintLp: mint_lp,
         vaultX: vault_x,
         vaultY: vault_y,
+        vaultLp: vault_lp,
         config: config,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgramX: TOKEN_PROGRAM_ID,
+        tokenProgramY: TOKEN_PROGRAM_ID,
+        tokenProgramLp: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId
       })
       .signers([initializer])
@@ -145,7 +158,7 @@ describe("amm", () => {
   it("Depositing ...!", async () => {
     // Add your test here.
     const tx = await program.methods
-      .deposit(amount, max_x, max_y)
+      .deposit(amount, max_x, max_y, [])
       .accountsStrict({
         user: user.publicKey,
         mintX: mint_x,
@@ -153,12 +166,65 @@ describe("amm", () => {
         mintLp: mint_lp,
         vaultX:vault_x,
         vaultY: vault_y,
+        vaultLp: vault_lp,
+        userX: user_x.address,
+        userY: user_y.address,
+        config: config,
+        userLp: user_lp,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgramX: TOKEN_PROGRAM_ID,
+        tokenProgramY: TOKEN_PROGRAM_ID,
+        tokenProgramLp: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc()
+
+    console.log("Your transaction signature", tx);
+  });
+
+  it("Swapping X for Y ...!", async () => {
+    const tx = await program.methods
+      .swap(true, new BN(1_000_000), new BN(1))
+      .accountsStrict({
+        user: user.publicKey,
+        mintX: mint_x,
+        mintY: mint_y,
+        mintLp: mint_lp,
+        vaultX: vault_x,
+        vaultY: vault_y,
+        userX: user_x.address,
+        userY: user_y.address,
+        config: config,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgramX: TOKEN_PROGRAM_ID,
+        tokenProgramY: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc()
+
+    console.log("Your transaction signature", tx);
+  });
+
+  it("Withdrawing ...!", async () => {
+    const tx = await program.methods
+      .withdraw(new BN(10_000_000), new BN(1), new BN(1))
+      .accountsStrict({
+        user: user.publicKey,
+        mintX: mint_x,
+        mintY: mint_y,
+        mintLp: mint_lp,
+        vaultX: vault_x,
+        vaultY: vault_y,
         userX: user_x.address,
         userY: user_y.address,
         config: config,
         userLp: user_lp,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        tokenProgramX: TOKEN_PROGRAM_ID,
+        tokenProgramY: TOKEN_PROGRAM_ID,
+        tokenProgramLp: TOKEN_PROGRAM_ID,
         systemProgram: SystemProgram.programId,
       })
       .signers([user])
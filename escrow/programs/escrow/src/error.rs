@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+#[error_code]
+pub enum EscrowError {
+    #[msg("Amount must be greater than zero")]
+    ZeroAmount,
+    #[msg("Amount exceeds what is left of the offer")]
+    AmountTooLarge,
+    #[msg("Fill is too small to release any tokens")]
+    FillTooSmall,
+    #[msg("Arithmetic overflow")]
+    Overflow,
+    #[msg("Expiry must be in the future")]
+    InvalidExpiry,
+    #[msg("Offer has expired")]
+    OfferExpired,
+    #[msg("Offer has not expired yet")]
+    OfferNotExpired,
+    #[msg("Offer is reserved for another taker")]
+    TakerNotAllowed,
+    #[msg("Token account required for a non-SOL leg is missing")]
+    MissingTokenAccount,
+    #[msg("Fill releases less than the taker's minimum")]
+    SlippageExceeded,
+    #[msg("Mint is not owned by the given token program")]
+    InvalidTokenProgram,
+    #[msg("Native SOL offers are held on the escrow and take no vault")]
+    UnexpectedVault,
+}
@@ -0,0 +1,9 @@
+use anchor_lang::prelude::*;
+
+#[event]
+pub struct EscrowUpdated {
+    pub escrow: Pubkey,
+    pub maker: Pubkey,
+    pub receive: u64,
+    pub deposit: u64,
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken, token_2022::{close_account, transfer_checked, CloseAccount, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::{error::EscrowError, state::{mint_uses_program, Escrow}};
+
+
+/// Permissionless cleanup of an expired offer. Whoever calls it pays for
+/// recreating `maker_ata_a` if the maker closed it; the vault balance and all
+/// rent go back to the maker.
+#[derive(Accounts)]
+pub struct Expire<'info> {
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(mut)]
+    pub maker: SystemAccount<'info>,
+
+    #[account(
+        constraint = mint_uses_program(&mint_a, &token_program) @ EscrowError::InvalidTokenProgram
+    )]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    /// Omitted when the offer is native SOL.
+    #[account(
+        init_if_needed,
+        payer = caller,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = mint_a,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+        close = maker
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Omitted when the offer is native SOL.
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> Expire<'info> {
+    pub fn expire_and_close(&mut self) -> Result<()> {
+        require!(self.escrow.is_expired(Clock::get()?.unix_timestamp), EscrowError::OfferNotExpired);
+
+        // A native SOL deposit is held on the escrow account and goes back to
+        // the maker when it closes.
+        if self.escrow.offers_sol() {
+            return Ok(());
+        }
+
+        let (Some(maker_ata_a), Some(vault)) = (&self.maker_ata_a, &self.vault) else {
+            return err!(EscrowError::MissingTokenAccount);
+        };
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"escrow",
+            self.escrow.maker.as_ref(),  
+            &self.escrow.seed.to_le_bytes(),
+            &[self.escrow.bump]
+        ]];
+
+        let transfer_accounts = TransferChecked{ 
+            from: vault.to_account_info(), 
+            mint: self.mint_a.to_account_info(), 
+            to: maker_ata_a.to_account_info(), 
+            authority: self.escrow.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), transfer_accounts, signer_seeds);
+
+        transfer_checked(cpi_ctx, vault.amount, self.mint_a.decimals)?;
+
+        let close_accounts = CloseAccount { 
+            account: vault.to_account_info(), 
+            destination: self.maker.to_account_info(), 
+            authority: self.escrow.to_account_info() 
+        };
+
+        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, signer_seeds);
+
+        close_account(ctx)?;
+        Ok(())
+    }
+}
@@ -1,11 +1,12 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
 use anchor_spl::{
     associated_token::AssociatedToken, 
+    token::spl_token::native_mint,
     token_2022::{transfer_checked, TransferChecked}, 
     token_interface::{Mint, TokenAccount, TokenInterface}
 };
 
-use crate::state::Escrow;
+use crate::{error::EscrowError, state::{mint_uses_program, Escrow}};
 
 #[derive(Accounts)]
 #[instruction(seed: u64)]
@@ -14,22 +15,23 @@ pub struct Make<'info> {
     pub maker: Signer<'info>,
 
     #[account(
-        mint::token_program = token_program
+        constraint = mint_uses_program(&mint_a, &token_program) @ EscrowError::InvalidTokenProgram
     )]
     pub mint_a: InterfaceAccount<'info, Mint>,
 
     #[account(
-        mint::token_program = token_program
+        constraint = mint_uses_program(&mint_b, &token_program) @ EscrowError::InvalidTokenProgram
     )]
     pub mint_b: InterfaceAccount<'info, Mint>,
 
+    /// Omitted when offering native SOL.
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = maker,
         associated_token::token_program = token_program
     )]
-    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    pub maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
@@ -40,6 +42,7 @@ pub struct Make<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
 
+    /// Omitted when offering native SOL.
     #[account(
         init,
         payer = maker,
@@ -47,7 +50,7 @@ pub struct Make<'info> {
         associated_token::authority = escrow,
         associated_token::token_program = token_program
     )]
-    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
@@ -55,23 +58,57 @@ pub struct Make<'info> {
 }
 
 impl<'info> Make<'info> {
-    pub fn initialize(&mut self, seed: u64, receive: u64, bumps: &MakeBumps) -> Result<()> {
+    pub fn initialize(
+        &mut self,
+        seed: u64,
+        receive: u64,
+        deposit: u64,
+        expires_at: Option<i64>,
+        taker: Option<Pubkey>,
+        bumps: &MakeBumps,
+    ) -> Result<()> {
+        require!(receive != 0 && deposit != 0, EscrowError::ZeroAmount);
+        // A vault for the native mint would never be used or closed, leaving
+        // its rent stranded.
+        require!(self.mint_a.key() != native_mint::ID || self.vault.is_none(), EscrowError::UnexpectedVault);
+        if let Some(expires_at) = expires_at {
+            require!(expires_at > Clock::get()?.unix_timestamp, EscrowError::InvalidExpiry);
+        }
+
         self.escrow.set_inner(Escrow { 
             seed, 
             maker: self.maker.key(), 
             mint_a: self.mint_a.key(), 
             mint_b: self.mint_b.key(), 
             receive, 
+            deposit,
+            expires_at,
+            taker,
             bump: bumps.escrow
         });
         Ok(())
     }
 
     pub fn deposit(&mut self, deposit: u64) -> Result<()> {
+        if self.escrow.offers_sol() {
+            let cpi_accounts = Transfer {
+                from: self.maker.to_account_info(),
+                to: self.escrow.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), cpi_accounts);
+
+            return transfer(cpi_ctx, deposit);
+        }
+
+        let (Some(maker_ata_a), Some(vault)) = (&self.maker_ata_a, &self.vault) else {
+            return err!(EscrowError::MissingTokenAccount);
+        };
+
         let cpi_account = TransferChecked {
-            from: self.maker_ata_a.to_account_info(),
+            from: maker_ata_a.to_account_info(),
             mint: self.mint_a.to_account_info(),
-            to: self.vault.to_account_info(),
+            to: vault.to_account_info(),
             authority: self.maker.to_account_info(),
         }; 
 
@@ -79,6 +116,14 @@ impl<'info> Make<'info> {
 
         transfer_checked(cpi_ctx, deposit, self.mint_a.decimals)?;
 
+        // A Token-2022 transfer fee can leave the vault with less than was
+        // sent; only what arrived is on offer, so fills are priced from it.
+        if let Some(vault) = self.vault.as_mut() {
+            vault.reload()?;
+            self.escrow.deposit = vault.amount;
+        }
+        require!(self.escrow.deposit != 0, EscrowError::ZeroAmount);
+
         Ok(())
     }
 }
\ No newline at end of file
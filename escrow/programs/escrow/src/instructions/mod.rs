@@ -1,7 +1,11 @@
 pub mod make;
 pub mod take;
 pub mod refund;
+pub mod expire;
+pub mod update;
 
 pub use make::*;
 pub use take::*;
-pub use refund::*;
\ No newline at end of file
+pub use refund::*;
+pub use expire::*;
+pub use update::*;
\ No newline at end of file
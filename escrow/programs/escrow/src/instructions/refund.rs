@@ -2,7 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken, token_2022::{close_account, transfer_checked, CloseAccount, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
 
-use crate::state::Escrow;
+use crate::{error::EscrowError, state::{mint_uses_program, Escrow}};
 
 
 #[derive(Accounts)]
@@ -11,17 +11,18 @@ pub struct Refund<'info> {
     pub maker: Signer<'info>,
 
     #[account(
-        mint::token_program = token_program
+        constraint = mint_uses_program(&mint_a, &token_program) @ EscrowError::InvalidTokenProgram
     )]
     pub mint_a: InterfaceAccount<'info, Mint>,
 
+    /// Omitted when the offer is native SOL.
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = maker,
         associated_token::token_program = token_program
     )]
-    pub maker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    pub maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
@@ -32,13 +33,14 @@ pub struct Refund<'info> {
     )]
     pub escrow: Account<'info, Escrow>,
 
+    /// Omitted when the offer is native SOL.
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
         associated_token::token_program = token_program
     )]
-    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
@@ -47,6 +49,16 @@ pub struct Refund<'info> {
 
 impl<'info> Refund<'info> {
     pub fn refund_and_close(&mut self) -> Result<()> {
+        // A native SOL deposit is held on the escrow account and goes back to
+        // the maker when it closes.
+        if self.escrow.offers_sol() {
+            return Ok(());
+        }
+
+        let (Some(maker_ata_a), Some(vault)) = (&self.maker_ata_a, &self.vault) else {
+            return err!(EscrowError::MissingTokenAccount);
+        };
+
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"escrow",
             self.escrow.maker.as_ref(),  
@@ -55,23 +67,23 @@ impl<'info> Refund<'info> {
         ]];
 
         let transfer_accounts = TransferChecked{ 
-            from: self.vault.to_account_info(), 
+            from: vault.to_account_info(), 
             mint: self.mint_a.to_account_info(), 
-            to: self.maker_ata_a.to_account_info(), 
+            to: maker_ata_a.to_account_info(), 
             authority: self.escrow.to_account_info()
         };
 
         let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), transfer_accounts, signer_seeds);
 
-        transfer_checked(cpi_ctx, self.vault.amount, self.mint_a.decimals)?;
+        transfer_checked(cpi_ctx, vault.amount, self.mint_a.decimals)?;
 
         let close_accounts = CloseAccount { 
-            account: self.vault.to_account_info(), 
+            account: vault.to_account_info(), 
             destination: self.maker.to_account_info(), 
             authority: self.escrow.to_account_info() 
         };
 
-        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, &signer_seeds);
+        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, signer_seeds);
 
         close_account(ctx)?;
         Ok(())
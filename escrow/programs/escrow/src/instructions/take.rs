@@ -1,7 +1,7 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
 use anchor_spl::{associated_token::AssociatedToken, token_2022::{close_account, transfer_checked, CloseAccount, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
 
-use crate::state::Escrow;
+use crate::{error::EscrowError, state::{mint_uses_program, Escrow}};
 
 #[derive(Accounts)]
 pub struct Take<'info> {
@@ -12,57 +12,61 @@ pub struct Take<'info> {
     pub taker: Signer<'info>,
 
     #[account(
-        mint::token_program = token_program
+        constraint = mint_uses_program(&mint_a, &token_program) @ EscrowError::InvalidTokenProgram
     )]
     pub mint_a: InterfaceAccount<'info, Mint>,
 
     #[account(
-        mint::token_program = token_program
+        constraint = mint_uses_program(&mint_b, &token_program) @ EscrowError::InvalidTokenProgram
     )]
     pub mint_b: InterfaceAccount<'info, Mint>,
 
+    /// Omitted when the offer is native SOL.
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = taker,
         associated_token::token_program = token_program
     )]
-    pub taker_ata_a: InterfaceAccount<'info, TokenAccount>,
+    pub taker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Omitted when paying in native SOL.
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = taker,
         associated_token::token_program = token_program
     )]
-    pub taker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    pub taker_ata_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
+    /// Omitted when paying in native SOL.
     #[account(
         mut,
         associated_token::mint = mint_b,
         associated_token::authority = maker,
         associated_token::token_program = token_program
     )]
-    pub maker_ata_b: InterfaceAccount<'info, TokenAccount>,
+    pub maker_ata_b: Option<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         mut,
         has_one = mint_a,
         has_one = mint_b,
         has_one = maker,
+        constraint = escrow.taker.is_none() || escrow.taker == Some(taker.key()) @ EscrowError::TakerNotAllowed,
         seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
         bump = escrow.bump,
-        close = maker
     )]
     pub escrow: Account<'info, Escrow>,
 
+    /// Omitted when the offer is native SOL.
     #[account(
         mut,
         associated_token::mint = mint_a,
         associated_token::authority = escrow,
         associated_token::token_program = token_program
     )]
-    pub vault: InterfaceAccount<'info, TokenAccount>,
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
 
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub token_program: Interface<'info, TokenInterface>,
@@ -70,22 +74,78 @@ pub struct Take<'info> {
 }
 
 impl<'info> Take<'info> {
-    pub fn deposit(&mut self) -> Result<()> {
+    /// Pays `amount` of `mint_b` towards what is left of `receive`.
+    pub fn deposit(&mut self, amount: u64) -> Result<()> {
+        require!(amount != 0, EscrowError::ZeroAmount);
+        require!(amount <= self.escrow.receive, EscrowError::AmountTooLarge);
+        require!(!self.escrow.is_expired(Clock::get()?.unix_timestamp), EscrowError::OfferExpired);
+
+        if self.escrow.wants_sol() {
+            let cpi_accounts = Transfer {
+                from: self.taker.to_account_info(),
+                to: self.maker.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), cpi_accounts);
+
+            return transfer(cpi_ctx, amount);
+        }
+
+        let (Some(taker_ata_b), Some(maker_ata_b)) = (&self.taker_ata_b, &self.maker_ata_b) else {
+            return err!(EscrowError::MissingTokenAccount);
+        };
+
         let cpi_account = TransferChecked {
-            from: self.taker_ata_b.to_account_info(),
+            from: taker_ata_b.to_account_info(),
             mint: self.mint_b.to_account_info(),
-            to: self.maker_ata_b.to_account_info(),
+            to: maker_ata_b.to_account_info(),
             authority: self.taker.to_account_info(),
         }; 
 
         let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_account);
 
-        transfer_checked(cpi_ctx, self.escrow.receive, self.mint_a.decimals)?;
+        transfer_checked(cpi_ctx, amount, self.mint_b.decimals)?;
 
         Ok(())
     }
 
-    pub fn withdraw_and_close(&mut self) -> Result<()> {
+    /// Releases the share of the deposit bought by paying `amount`, at the
+    /// maker's ratio and rounded down. The fill that completes the offer
+    /// takes whatever is left of the deposit.
+    ///
+    /// Fails if that is less than `min_release`, so a taker can't be filled
+    /// at worse terms by an `update` landing just before the take.
+    pub fn withdraw(&mut self, amount: u64, min_release: u64) -> Result<()> {
+        let release = match amount == self.escrow.receive {
+            true => match (self.escrow.offers_sol(), &self.vault) {
+                (false, Some(vault)) => vault.amount,
+                _ => self.escrow.deposit,
+            },
+            false => {
+                let release = (self.escrow.deposit as u128)
+                    .checked_mul(amount as u128)
+                    .ok_or(EscrowError::Overflow)?
+                    / self.escrow.receive as u128;
+                release as u64
+            }
+        };
+        require!(release != 0, EscrowError::FillTooSmall);
+        require!(release >= min_release, EscrowError::SlippageExceeded);
+
+        self.escrow.receive -= amount;
+        self.escrow.deposit = self.escrow.deposit.saturating_sub(release);
+
+        if self.escrow.offers_sol() {
+            self.escrow.sub_lamports(release)?;
+            self.taker.add_lamports(release)?;
+
+            return Ok(());
+        }
+
+        let (Some(vault), Some(taker_ata_a)) = (&self.vault, &self.taker_ata_a) else {
+            return err!(EscrowError::MissingTokenAccount);
+        };
+
         let signer_seeds: &[&[&[u8]]] = &[&[
             b"escrow",
             self.escrow.maker.as_ref(),  
@@ -94,26 +154,41 @@ impl<'info> Take<'info> {
         ]];
 
         let transfer_accounts = TransferChecked{ 
-            from: self.vault.to_account_info(), 
+            from: vault.to_account_info(), 
             mint: self.mint_a.to_account_info(), 
-            to: self.taker_ata_a.to_account_info(), 
+            to: taker_ata_a.to_account_info(), 
             authority: self.escrow.to_account_info()
         };
 
         let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), transfer_accounts, signer_seeds);
 
-        transfer_checked(cpi_ctx, self.vault.amount, self.mint_a.decimals)?;
+        transfer_checked(cpi_ctx, release, self.mint_a.decimals)?;
 
-        let close_accounts = CloseAccount { 
-            account: self.vault.to_account_info(), 
-            destination: self.maker.to_account_info(), 
-            authority: self.escrow.to_account_info() 
-        };
+        Ok(())
+    }
+
+    /// Closes the vault and escrow to the maker once the offer is filled.
+    pub fn close(&mut self) -> Result<()> {
+        if let (false, Some(vault)) = (self.escrow.offers_sol(), &self.vault) {
+            let signer_seeds: &[&[&[u8]]] = &[&[
+                b"escrow",
+                self.escrow.maker.as_ref(),  
+                &self.escrow.seed.to_le_bytes(),
+                &[self.escrow.bump]
+            ]];
+
+            let close_accounts = CloseAccount { 
+                account: vault.to_account_info(), 
+                destination: self.maker.to_account_info(), 
+                authority: self.escrow.to_account_info() 
+            };
 
-        let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, &signer_seeds);
+            let ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), close_accounts, signer_seeds);
 
-        close_account(ctx)?;
+            close_account(ctx)?;
+        }
 
+        self.escrow.close(self.maker.to_account_info())?;
 
         Ok(())
     }
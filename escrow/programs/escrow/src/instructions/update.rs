@@ -0,0 +1,143 @@
+use anchor_lang::{prelude::*, system_program::{transfer, Transfer}};
+use anchor_spl::{
+    associated_token::AssociatedToken, token_2022::{transfer_checked, TransferChecked}, token_interface::{Mint, TokenAccount, TokenInterface}};
+
+use crate::{error::EscrowError, events::EscrowUpdated, state::{mint_uses_program, Escrow}};
+
+
+#[derive(Accounts)]
+pub struct Update<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        constraint = mint_uses_program(&mint_a, &token_program) @ EscrowError::InvalidTokenProgram
+    )]
+    pub mint_a: InterfaceAccount<'info, Mint>,
+
+    /// Omitted when the offer is native SOL.
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = maker,
+        associated_token::token_program = token_program
+    )]
+    pub maker_ata_a: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        has_one = maker,
+        has_one = mint_a,
+        seeds = [b"escrow", maker.key().as_ref(), escrow.seed.to_le_bytes().as_ref()],
+        bump = escrow.bump,
+    )]
+    pub escrow: Account<'info, Escrow>,
+
+    /// Omitted when the offer is native SOL.
+    #[account(
+        mut,
+        associated_token::mint = mint_a,
+        associated_token::authority = escrow,
+        associated_token::token_program = token_program
+    )]
+    pub vault: Option<InterfaceAccount<'info, TokenAccount>>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>
+}
+
+impl<'info> Update<'info> {
+    /// Replaces the remaining terms with `receive` and `deposit`, moving the
+    /// difference in deposit into or out of the vault. A top-up is credited
+    /// with what reaches the vault, net of any Token-2022 transfer fee.
+    pub fn update(&mut self, receive: u64, mut deposit: u64) -> Result<()> {
+        require!(receive != 0 && deposit != 0, EscrowError::ZeroAmount);
+
+        let current = self.escrow.deposit;
+        if deposit > current {
+            deposit = current + self.top_up(deposit - current)?;
+        } else if deposit < current {
+            self.withdraw(current - deposit)?;
+        }
+
+        self.escrow.receive = receive;
+        self.escrow.deposit = deposit;
+
+        emit!(EscrowUpdated {
+            escrow: self.escrow.key(),
+            maker: self.maker.key(),
+            receive,
+            deposit,
+        });
+
+        Ok(())
+    }
+
+    /// Moves `amount` into the escrow and returns how much arrived.
+    fn top_up(&mut self, amount: u64) -> Result<u64> {
+        if self.escrow.offers_sol() {
+            let cpi_accounts = Transfer {
+                from: self.maker.to_account_info(),
+                to: self.escrow.to_account_info(),
+            };
+
+            let cpi_ctx = CpiContext::new(self.system_program.to_account_info(), cpi_accounts);
+
+            transfer(cpi_ctx, amount)?;
+            return Ok(amount);
+        }
+
+        let (Some(maker_ata_a), Some(vault)) = (&self.maker_ata_a, &mut self.vault) else {
+            return err!(EscrowError::MissingTokenAccount);
+        };
+        let before = vault.amount;
+
+        let cpi_account = TransferChecked {
+            from: maker_ata_a.to_account_info(),
+            mint: self.mint_a.to_account_info(),
+            to: vault.to_account_info(),
+            authority: self.maker.to_account_info(),
+        }; 
+
+        let cpi_ctx = CpiContext::new(self.token_program.to_account_info(), cpi_account);
+
+        transfer_checked(cpi_ctx, amount, self.mint_a.decimals)?;
+
+        vault.reload()?;
+        Ok(vault.amount - before)
+    }
+
+    fn withdraw(&mut self, amount: u64) -> Result<()> {
+        if self.escrow.offers_sol() {
+            self.escrow.sub_lamports(amount)?;
+            self.maker.add_lamports(amount)?;
+
+            return Ok(());
+        }
+
+        let (Some(maker_ata_a), Some(vault)) = (&self.maker_ata_a, &self.vault) else {
+            return err!(EscrowError::MissingTokenAccount);
+        };
+
+        let signer_seeds: &[&[&[u8]]] = &[&[
+            b"escrow",
+            self.escrow.maker.as_ref(),  
+            &self.escrow.seed.to_le_bytes(),
+            &[self.escrow.bump]
+        ]];
+
+        let transfer_accounts = TransferChecked{ 
+            from: vault.to_account_info(), 
+            mint: self.mint_a.to_account_info(), 
+            to: maker_ata_a.to_account_info(), 
+            authority: self.escrow.to_account_info()
+        };
+
+        let cpi_ctx = CpiContext::new_with_signer(self.token_program.to_account_info(), transfer_accounts, signer_seeds);
+
+        transfer_checked(cpi_ctx, amount, self.mint_a.decimals)?;
+
+        Ok(())
+    }
+}
@@ -5,6 +5,8 @@ declare_id!("FueDhRTexJfY9TXVXTSQaQQHkwMP6Enq6AJn4BPhbV51");
 
 mod state;
 mod instructions;
+mod error;
+mod events;
 
 use instructions::*;
 
@@ -13,16 +15,27 @@ pub mod escrow {
 
     use super::*;
 
-    pub fn make(ctx: Context<Make>, seed: u64, receive: u64, deposit: u64) -> Result<()> {
-        ctx.accounts.initialize(seed, receive, &ctx.bumps)?;
+    pub fn make(
+        ctx: Context<Make>,
+        seed: u64,
+        receive: u64,
+        deposit: u64,
+        expires_at: Option<i64>,
+        taker: Option<Pubkey>,
+    ) -> Result<()> {
+        ctx.accounts.initialize(seed, receive, deposit, expires_at, taker, &ctx.bumps)?;
         ctx.accounts.deposit(deposit)?;
 
         Ok(())  
     }
 
-    pub fn take(ctx: Context<Take>) -> Result<()> {
-        ctx.accounts.deposit()?;
-        ctx.accounts.withdraw_and_close()?;
+    pub fn take(ctx: Context<Take>, amount: u64, min_release: u64) -> Result<()> {
+        ctx.accounts.deposit(amount)?;
+        ctx.accounts.withdraw(amount, min_release)?;
+
+        if ctx.accounts.escrow.receive == 0 {
+            ctx.accounts.close()?;
+        }
 
         Ok(())  
     }
@@ -33,4 +46,16 @@ pub mod escrow {
         Ok(())  
     }
 
+    pub fn expire(ctx: Context<Expire>) -> Result<()> {
+        ctx.accounts.expire_and_close()?;
+
+        Ok(())  
+    }
+
+    pub fn update(ctx: Context<Update>, receive: u64, deposit: u64) -> Result<()> {
+        ctx.accounts.update(receive, deposit)?;
+
+        Ok(())  
+    }
+
 }
\ No newline at end of file
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use anchor_spl::{token::spl_token::native_mint, token_interface::{Mint, TokenInterface}};
 
 #[account]
 #[derive(InitSpace)]
@@ -7,6 +8,38 @@ pub struct Escrow {
     pub maker: Pubkey,
     pub mint_a: Pubkey,
     pub mint_b: Pubkey,
+    /// Amount of `mint_b` still wanted for the unfilled part of the offer.
     pub receive: u64,
+    /// Amount of `mint_a` still offered, released pro rata as `receive` is paid.
+    /// This is what the vault received, net of any Token-2022 transfer fee.
+    pub deposit: u64,
+    /// Unix timestamp from which the offer can no longer be taken.
+    pub expires_at: Option<i64>,
+    /// The only wallet allowed to take the offer, for private trades.
+    pub taker: Option<Pubkey>,
     pub bump: u8
-}
\ No newline at end of file
+}
+
+impl Escrow {
+    /// The offered side is native SOL, held as lamports on the escrow
+    /// account instead of in a vault.
+    pub fn offers_sol(&self) -> bool {
+        self.mint_a == native_mint::ID
+    }
+
+    /// The requested side is native SOL, paid straight to the maker's wallet.
+    pub fn wants_sol(&self) -> bool {
+        self.mint_b == native_mint::ID
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+/// Whether `mint` belongs to `token_program`. The native mint is owned by the
+/// legacy token program, but SOL legs move lamports and never touch it, so it
+/// pairs with Token-2022 just as well.
+pub fn mint_uses_program(mint: &InterfaceAccount<Mint>, token_program: &Interface<TokenInterface>) -> bool {
+    mint.key() == native_mint::ID || *mint.to_account_info().owner == token_program.key()
+}
@@ -1,9 +1,10 @@
 import * as anchor from "@coral-xyz/anchor";
 import { Program, BN } from "@coral-xyz/anchor";
 import { Escrow } from "../target/types/escrow";
-import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram } from "@solana/web3.js";
-import { Account, ASSOCIATED_TOKEN_PROGRAM_ID, createMint, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token";
+import { Keypair, LAMPORTS_PER_SOL, PublicKey, SystemProgram, Transaction, sendAndConfirmTransaction } from "@solana/web3.js";
+import { Account, ASSOCIATED_TOKEN_PROGRAM_ID, createInitializeMintInstruction, createInitializeTransferFeeConfigInstruction, createMint, ExtensionType, getAccount, getMintLen, NATIVE_MINT, getAssociatedTokenAddressSync, getOrCreateAssociatedTokenAccount, mintTo, TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID } from "@solana/spl-token";
 import { randomBytes } from "node:crypto"
+import { expect } from "chai";
 import { confirmTransaction } from "@solana-developers/helpers";
 import { ASSOCIATED_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/utils/token";
 
@@ -154,9 +155,10 @@ describe("escrow", () => {
   });
 
   it("Making Escrow!", async () => {
-    // Add your test here.
-    const tx = await program.methods
-      .make(seeds, new BN(1), new BN(1))
+    const makerBefore = await tokenBalance(connection, maker_ata_a.address);
+
+    await program.methods
+      .make(seeds, new BN(1), new BN(1), null, null)
       .accountsPartial({
         maker: maker.publicKey,
         mintA: mint_a,
@@ -169,13 +171,17 @@ describe("escrow", () => {
         systemProgram: SystemProgram.programId
       })
       .signers([maker])
-      .rpc()
-    console.log("Your transaction signature", tx);
+      .rpc();
+
+    expect(await tokenBalance(connection, maker_ata_a.address)).to.equal(makerBefore - 1);
+    expect(await tokenBalance(connection, vault)).to.equal(1);
+    expect((await program.account.escrow.fetch(escrow)).deposit.toNumber()).to.equal(1);
   });
 
   it("Requesting Refund!", async () => {
-    // Add your test here.
-    const tx = await program.methods
+    const makerBefore = await tokenBalance(connection, maker_ata_a.address);
+
+    await program.methods
       .refund()
       .accountsPartial({
         maker: maker.publicKey,
@@ -188,13 +194,16 @@ describe("escrow", () => {
         systemProgram: SystemProgram.programId
       })
       .signers([maker])
-      .rpc()
-    console.log("Your transaction signature", tx);
+      .rpc();
+
+    expect(await tokenBalance(connection, maker_ata_a.address)).to.equal(makerBefore + 1);
+    expect(await connection.getAccountInfo(vault)).to.be.null;
+    expect(await connection.getAccountInfo(escrow)).to.be.null;
   });
+
   it("Making Escrow Again!", async () => {
-    // Add your test here.
-    const tx = await program.methods
-      .make(seeds, new BN(1), new BN(1))
+    await program.methods
+      .make(seeds, new BN(2), new BN(2), null, taker.publicKey)
       .accountsPartial({
         maker: maker.publicKey,
         mintA: mint_a,
@@ -207,14 +216,111 @@ describe("escrow", () => {
         systemProgram: SystemProgram.programId
       })
       .signers([maker])
-      .rpc()
-    console.log("Your transaction signature", tx);
+      .rpc();
+
+    expect(await tokenBalance(connection, vault)).to.equal(2);
+  });
+
+  it("Updating Escrow!", async () => {
+    // Tops the deposit up from 2 to 3 at the same requested amount.
+    const makerBefore = await tokenBalance(connection, maker_ata_a.address);
+
+    await program.methods
+      .update(new BN(2), new BN(3))
+      .accountsPartial({
+        maker: maker.publicKey,
+        mintA: mint_a,
+        makerAtaA: maker_ata_a.address,
+        escrow: escrow,
+        vault: vault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([maker])
+      .rpc();
+
+    const updated = await program.account.escrow.fetch(escrow);
+    expect(updated.receive.toNumber()).to.equal(2);
+    expect(updated.deposit.toNumber()).to.equal(3);
+    expect(await tokenBalance(connection, vault)).to.equal(3);
+    expect(await tokenBalance(connection, maker_ata_a.address)).to.equal(makerBefore - 1);
+  });
+
+  it("Rejecting Other Takers!", async () => {
+    // The offer is reserved for `taker`, so any other wallet is turned away.
+    const stranger = Keypair.generate();
+    await airdrop(connection, stranger.publicKey, 1);
+    const stranger_ata_a = await getOrCreateAssociatedTokenAccount(connection, stranger, mint_a, stranger.publicKey, undefined, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    const stranger_ata_b = await getOrCreateAssociatedTokenAccount(connection, stranger, mint_b, stranger.publicKey, undefined, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+
+    let error;
+    try {
+      await program.methods
+        .take(new BN(1), new BN(1))
+        .accountsPartial({
+          maker: maker.publicKey,
+          taker: stranger.publicKey,
+          mintA: mint_a,
+          mintB: mint_b,
+          takerAtaA: stranger_ata_a.address,
+          takerAtaB: stranger_ata_b.address,
+          makerAtaB: maker_ata_b.address,
+          escrow: escrow,
+          vault: vault,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId
+        })
+        .signers([stranger])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    expect(error?.error?.errorCode?.code).to.equal("TakerNotAllowed");
+  });
+
+  it("Testing Partial Take!", async () => {
+    // Paying 1 of the 2 requested releases floor(3 * 1 / 2) = 1.
+    const takerABefore = await tokenBalance(connection, taker_ata_a.address);
+    const takerBBefore = await tokenBalance(connection, taker_ata_b.address);
+    const makerBBefore = await tokenBalance(connection, maker_ata_b.address);
+
+    await program.methods
+      .take(new BN(1), new BN(1))
+      .accountsPartial({
+        maker: maker.publicKey,
+        taker: taker.publicKey,
+        mintA: mint_a,
+        mintB: mint_b,
+        takerAtaA: taker_ata_a.address,
+        takerAtaB: taker_ata_b.address,
+        makerAtaB: maker_ata_b.address,
+        escrow: escrow,
+        vault: vault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([taker])
+      .rpc();
+
+    const remaining = await program.account.escrow.fetch(escrow);
+    expect(remaining.receive.toNumber()).to.equal(1);
+    expect(remaining.deposit.toNumber()).to.equal(2);
+    expect(await tokenBalance(connection, vault)).to.equal(2);
+    expect(await tokenBalance(connection, taker_ata_a.address)).to.equal(takerABefore + 1);
+    expect(await tokenBalance(connection, taker_ata_b.address)).to.equal(takerBBefore - 1);
+    expect(await tokenBalance(connection, maker_ata_b.address)).to.equal(makerBBefore + 1);
   });
 
   it("Testing Take!", async () => {
-    // Add your test here.
-    const tx = await program.methods
-      .take()
+    // The completing fill takes the rest of the vault and closes the offer.
+    const takerABefore = await tokenBalance(connection, taker_ata_a.address);
+    const makerBBefore = await tokenBalance(connection, maker_ata_b.address);
+
+    await program.methods
+      .take(new BN(1), new BN(2))
       .accountsPartial({
         maker: maker.publicKey,
         taker: taker.publicKey,
@@ -230,8 +336,263 @@ describe("escrow", () => {
         systemProgram: SystemProgram.programId
       })
       .signers([taker])
-      .rpc()
-    console.log("Your transaction signature", tx);
+      .rpc();
+
+    expect(await tokenBalance(connection, taker_ata_a.address)).to.equal(takerABefore + 2);
+    expect(await tokenBalance(connection, maker_ata_b.address)).to.equal(makerBBefore + 1);
+    expect(await connection.getAccountInfo(vault)).to.be.null;
+    expect(await connection.getAccountInfo(escrow)).to.be.null;
+  });
+
+  it("Rejecting Takes After A Worse Update!", async () => {
+    const updatedSeeds = new BN(randomBytes(8));
+    const [updatedEscrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), maker.publicKey.toBuffer(), updatedSeeds.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const updatedVault = getAssociatedTokenAddressSync(mint_a, updatedEscrow, true, TOKEN_2022_PROGRAM_ID);
+
+    await program.methods
+      .make(updatedSeeds, new BN(10), new BN(10), null, null)
+      .accountsPartial({
+        maker: maker.publicKey,
+        mintA: mint_a,
+        mintB: mint_b,
+        makerAtaA: maker_ata_a.address,
+        escrow: updatedEscrow,
+        vault: updatedVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([maker])
+      .rpc();
+
+    // The taker quotes 5 for 5, but the maker cuts the deposit first.
+    await program.methods
+      .update(new BN(10), new BN(4))
+      .accountsPartial({
+        maker: maker.publicKey,
+        mintA: mint_a,
+        makerAtaA: maker_ata_a.address,
+        escrow: updatedEscrow,
+        vault: updatedVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([maker])
+      .rpc();
+
+    let error;
+    try {
+      await program.methods
+        .take(new BN(5), new BN(5))
+        .accountsPartial({
+          maker: maker.publicKey,
+          taker: taker.publicKey,
+          mintA: mint_a,
+          mintB: mint_b,
+          takerAtaA: taker_ata_a.address,
+          takerAtaB: taker_ata_b.address,
+          makerAtaB: maker_ata_b.address,
+          escrow: updatedEscrow,
+          vault: updatedVault,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId
+        })
+        .signers([taker])
+        .rpc();
+    } catch (err) {
+      error = err;
+    }
+    expect(error?.error?.errorCode?.code).to.equal("SlippageExceeded");
+  });
+
+  it("Expiring Escrow!", async () => {
+    const expiringSeeds = new BN(randomBytes(8));
+    const [expiringEscrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), maker.publicKey.toBuffer(), expiringSeeds.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const expiringVault = getAssociatedTokenAddressSync(mint_a, expiringEscrow, true, TOKEN_2022_PROGRAM_ID);
+
+    await program.methods
+      .make(expiringSeeds, new BN(1), new BN(1), new BN(Math.floor(Date.now() / 1000) + 2), null)
+      .accountsPartial({
+        maker: maker.publicKey,
+        mintA: mint_a,
+        mintB: mint_b,
+        makerAtaA: maker_ata_a.address,
+        escrow: expiringEscrow,
+        vault: expiringVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([maker])
+      .rpc();
+
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+
+    // Anyone can sweep an expired offer; the taker acts as the keeper here.
+    const makerBefore = await tokenBalance(connection, maker_ata_a.address);
+
+    await program.methods
+      .expire()
+      .accountsPartial({
+        caller: taker.publicKey,
+        maker: maker.publicKey,
+        mintA: mint_a,
+        makerAtaA: maker_ata_a.address,
+        escrow: expiringEscrow,
+        vault: expiringVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([taker])
+      .rpc();
+
+    expect(await tokenBalance(connection, maker_ata_a.address)).to.equal(makerBefore + 1);
+    expect(await connection.getAccountInfo(expiringVault)).to.be.null;
+    expect(await connection.getAccountInfo(expiringEscrow)).to.be.null;
+  });
+
+  it("Trading Native SOL!", async () => {
+    // The SOL leg never touches a token program, so the other leg can be a
+    // Token-2022 mint.
+    const solSeeds = new BN(randomBytes(8));
+    const [solEscrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), maker.publicKey.toBuffer(), solSeeds.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+
+    // Offers 1 SOL for 10 tokens; no vault or wrapped SOL account is needed.
+    await program.methods
+      .make(solSeeds, new BN(10 * 10 ** 6), new BN(LAMPORTS_PER_SOL), null, null)
+      .accountsPartial({
+        maker: maker.publicKey,
+        mintA: NATIVE_MINT,
+        mintB: mint_b,
+        makerAtaA: null,
+        escrow: solEscrow,
+        vault: null,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([maker])
+      .rpc();
+
+    const takerBefore = await getBalance(connection, taker.publicKey);
+    const makerBBefore = await tokenBalance(connection, maker_ata_b.address);
+
+    await program.methods
+      .take(new BN(10 * 10 ** 6), new BN(LAMPORTS_PER_SOL))
+      .accountsPartial({
+        maker: maker.publicKey,
+        taker: taker.publicKey,
+        mintA: NATIVE_MINT,
+        mintB: mint_b,
+        takerAtaA: null,
+        takerAtaB: taker_ata_b.address,
+        makerAtaB: maker_ata_b.address,
+        escrow: solEscrow,
+        vault: null,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([taker])
+      .rpc();
+
+    // The provider wallet pays the transaction fee, so the taker nets the
+    // full SOL deposit.
+    expect(await getBalance(connection, taker.publicKey)).to.equal(takerBefore + LAMPORTS_PER_SOL);
+    expect(await tokenBalance(connection, maker_ata_b.address)).to.equal(makerBBefore + 10 * 10 ** 6);
+    expect(await connection.getAccountInfo(solEscrow)).to.be.null;
+  });
+
+  it("Pricing Fills From What The Vault Received!", async () => {
+    // Mint A charges a 1% transfer fee, so the vault receives 9_900 of the
+    // 10_000 deposited and that is what fills are priced against.
+    const feeMint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports = await connection.getMinimumBalanceForRentExemption(mintLen);
+    await sendAndConfirmTransaction(
+      connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: maker.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(feeMint.publicKey, maker.publicKey, maker.publicKey, 100, BigInt(1_000_000), TOKEN_2022_PROGRAM_ID),
+        createInitializeMintInstruction(feeMint.publicKey, 6, maker.publicKey, null, TOKEN_2022_PROGRAM_ID)
+      ),
+      [maker, feeMint]
+    );
+
+    const fee_maker_ata = await getOrCreateAssociatedTokenAccount(connection, maker, feeMint.publicKey, maker.publicKey, undefined, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    const fee_taker_ata = await getOrCreateAssociatedTokenAccount(connection, taker, feeMint.publicKey, taker.publicKey, undefined, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+    await mintTo(connection, maker, feeMint.publicKey, fee_maker_ata.address, maker, 10_000, undefined, undefined, TOKEN_2022_PROGRAM_ID);
+
+    const feeSeeds = new BN(randomBytes(8));
+    const [feeEscrow] = PublicKey.findProgramAddressSync(
+      [Buffer.from("escrow"), maker.publicKey.toBuffer(), feeSeeds.toArrayLike(Buffer, "le", 8)],
+      program.programId
+    );
+    const feeVault = getAssociatedTokenAddressSync(feeMint.publicKey, feeEscrow, true, TOKEN_2022_PROGRAM_ID);
+
+    await program.methods
+      .make(feeSeeds, new BN(10), new BN(10_000), null, null)
+      .accountsPartial({
+        maker: maker.publicKey,
+        mintA: feeMint.publicKey,
+        mintB: mint_b,
+        makerAtaA: fee_maker_ata.address,
+        escrow: feeEscrow,
+        vault: feeVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([maker])
+      .rpc();
+
+    expect(await tokenBalance(connection, feeVault)).to.equal(9_900);
+    expect((await program.account.escrow.fetch(feeEscrow)).deposit.toNumber()).to.equal(9_900);
+
+    // Half the requested amount releases half of what the vault holds; the
+    // taker receives that net of the 1% fee, rounded up by the mint.
+    await program.methods
+      .take(new BN(5), new BN(4_950))
+      .accountsPartial({
+        maker: maker.publicKey,
+        taker: taker.publicKey,
+        mintA: feeMint.publicKey,
+        mintB: mint_b,
+        takerAtaA: fee_taker_ata.address,
+        takerAtaB: taker_ata_b.address,
+        makerAtaB: maker_ata_b.address,
+        escrow: feeEscrow,
+        vault: feeVault,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId
+      })
+      .signers([taker])
+      .rpc();
+
+    const remaining = await program.account.escrow.fetch(feeEscrow);
+    expect(remaining.receive.toNumber()).to.equal(5);
+    expect(remaining.deposit.toNumber()).to.equal(4_950);
+    expect(await tokenBalance(connection, feeVault)).to.equal(4_950);
+    expect(await tokenBalance(connection, fee_taker_ata.address)).to.equal(4_900);
   });
 });
 
@@ -257,3 +618,9 @@ async function getBalance(connection: anchor.web3.Connection, address: PublicKey
   return accountInfo.lamports;
 }
 
+async function tokenBalance(connection: anchor.web3.Connection, address: PublicKey) {
+  let account = await getAccount(connection, address, undefined, TOKEN_2022_PROGRAM_ID);
+
+  return Number(account.amount);
+}
+